@@ -1,55 +1,24 @@
 advent_of_code::solution!(3);
-use std::{collections::BTreeMap, ops::Deref};
+use std::collections::BTreeMap;
 
-#[derive(Debug)]
-struct Grid(Vec<Vec<char>>);
-impl Grid {
-    fn is_symbol(&self, x: isize, y: isize) -> bool {
-        if x < 0 || y < 0 {
-            return false;
-        }
-        let x = x as usize;
-        let y = y as usize;
+use advent_of_code::grid::Grid;
 
-        if x > self[0].len() - 1 || y > self.len() - 1 {
-            false
-        } else {
-            let c = self[y][x];
-            c != '.' && !c.is_numeric()
-        }
-    }
-
-    fn is_gear_symbol(&self, x: isize, y: isize) -> Option<(usize, usize)> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        let x = x as usize;
-        let y = y as usize;
-
-        if x > self[0].len() - 1 || y > self.len() - 1 {
-            None
-        } else {
-            // println!("grid[{}][{}] = '{}'", x, y, &c);
-            if self[y][x] == '*' {
-                Some((x, y))
-            } else {
-                None
-            }
-        }
-    }
+fn is_symbol(grid: &Grid<char>, x: isize, y: isize) -> bool {
+    grid.get(x, y).is_some_and(|c| *c != '.' && !c.is_numeric())
 }
-impl Deref for Grid {
-    type Target = Vec<Vec<char>>;
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+fn is_gear_symbol(grid: &Grid<char>, x: isize, y: isize) -> Option<(usize, usize)> {
+    if grid.get(x, y) == Some(&'*') {
+        Some((x as usize, y as usize))
+    } else {
+        None
     }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let grid = parse_grid(input);
     let mut parts = vec![];
-    grid.iter().enumerate().for_each(|(y, grid_line)| {
+    grid.rows().enumerate().for_each(|(y, grid_line)| {
         let mut adj = false;
         let mut part_num = vec![];
         grid_line.iter().enumerate().for_each(|(x, c)| {
@@ -69,14 +38,14 @@ pub fn part_one(input: &str) -> Option<u32> {
                 let x = x as isize;
                 let y = y as isize;
                 if !adj {
-                    adj = grid.is_symbol(x - 1, y)
-                        || grid.is_symbol(x + 1, y)
-                        || grid.is_symbol(x, y - 1)
-                        || grid.is_symbol(x, y + 1)
-                        || grid.is_symbol(x + 1, y + 1)
-                        || grid.is_symbol(x + 1, y - 1)
-                        || grid.is_symbol(x - 1, y - 1)
-                        || grid.is_symbol(x - 1, y + 1)
+                    adj = is_symbol(&grid, x - 1, y)
+                        || is_symbol(&grid, x + 1, y)
+                        || is_symbol(&grid, x, y - 1)
+                        || is_symbol(&grid, x, y + 1)
+                        || is_symbol(&grid, x + 1, y + 1)
+                        || is_symbol(&grid, x + 1, y - 1)
+                        || is_symbol(&grid, x - 1, y - 1)
+                        || is_symbol(&grid, x - 1, y + 1)
                 }
             }
         })
@@ -87,7 +56,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 pub fn part_two(input: &str) -> Option<u32> {
     let grid = parse_grid(input);
     let mut parts: BTreeMap<(usize, usize), Vec<u32>> = BTreeMap::new(); // keep track of the gear coord, and which parts are next to it
-    grid.iter().enumerate().for_each(|(y, grid_line)| {
+    grid.rows().enumerate().for_each(|(y, grid_line)| {
         let mut attached_gear: Option<(usize, usize)> = None;
         let mut part_num = vec![];
         grid_line.iter().enumerate().for_each(|(x, c)| {
@@ -109,14 +78,14 @@ pub fn part_two(input: &str) -> Option<u32> {
                 let y = y as isize;
                 if attached_gear.is_none() {
                     let checks: Vec<_> = vec![
-                        grid.is_gear_symbol(x - 1, y),
-                        grid.is_gear_symbol(x + 1, y),
-                        grid.is_gear_symbol(x, y - 1),
-                        grid.is_gear_symbol(x, y + 1),
-                        grid.is_gear_symbol(x + 1, y + 1),
-                        grid.is_gear_symbol(x + 1, y - 1),
-                        grid.is_gear_symbol(x - 1, y - 1),
-                        grid.is_gear_symbol(x - 1, y + 1),
+                        is_gear_symbol(&grid, x - 1, y),
+                        is_gear_symbol(&grid, x + 1, y),
+                        is_gear_symbol(&grid, x, y - 1),
+                        is_gear_symbol(&grid, x, y + 1),
+                        is_gear_symbol(&grid, x + 1, y + 1),
+                        is_gear_symbol(&grid, x + 1, y - 1),
+                        is_gear_symbol(&grid, x - 1, y - 1),
+                        is_gear_symbol(&grid, x - 1, y + 1),
                     ]
                     .iter()
                     .filter_map(|v| *v)
@@ -145,8 +114,8 @@ pub fn part_two(input: &str) -> Option<u32> {
     )
 }
 
-fn parse_grid(input: &str) -> Grid {
-    Grid(input.lines().map(|line| line.chars().collect()).collect())
+fn parse_grid(input: &str) -> Grid<char> {
+    Grid::parse(input, |c| c)
 }
 
 #[cfg(test)]
@@ -1,167 +1,35 @@
+use advent_of_code::interval::{Interval, IntervalSet, RangeMap};
 use itertools::Itertools;
-use std::collections::BTreeMap;
 
 advent_of_code::solution!(5);
 
-pub fn part_one(input: &str) -> Option<u32> {
+pub fn part_one(input: &str) -> Option<u64> {
     let almanac = parse(input);
-    let seeds = almanac.seeds.clone();
-    let result = compute_min_location(almanac, seeds);
-    Some(result)
+    let maps = almanac.range_maps();
+    // every seed goes through the maps on its own
+    almanac
+        .seeds
+        .iter()
+        .map(|seed| maps.iter().fold(*seed, |value, map| map.apply(value)))
+        .min()
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    todo!("NOPE, still too slow - have to look at mathematical solutions");
-
+pub fn part_two(input: &str) -> Option<u64> {
     let almanac = parse(input);
-    let mut seeds = vec![];
 
-    almanac
+    // way too many seeds to expand the ranges, so push whole intervals through the maps instead.
+    let seeds: IntervalSet<u64> = almanac
         .seeds
         .iter()
-        .chunks(2)
-        .into_iter()
-        .for_each(|mut chunk| {
-            let start = *chunk.next().unwrap();
-            let length = *chunk.next().unwrap();
-            (start..start + length).for_each(|s| {
-                seeds.push(s);
-            });
-        });
-    let result = compute_min_location(almanac, seeds);
-    Some(result)
-}
+        .tuples()
+        .map(|(start, length)| Interval::from_len(*start, *length))
+        .collect();
 
-pub fn compute_min_location(almanac: Almanac, seeds: Vec<u64>) -> u32 {
-    // ok, keeping path of mappings is unnecessary and too much memory, really just need one value
-    let mut paths = BTreeMap::new();
-    seeds.iter().for_each(|seed| {
-        paths.insert(seed, seed.clone());
-    });
-    seeds.iter().for_each(|seed| {
-        let mut found = false;
-        almanac.seeds_to_soil.iter().for_each(|rule| {
-            if (rule.source_range_start..rule.source_range_start + rule.range_length)
-                .contains(&seed)
-            {
-                // figure out index in source_range, then translate over to dest_range_start
-                let dx = seed - rule.source_range_start;
-                let dest_value = rule.dest_range_start + dx;
-                paths.entry(seed).and_modify(|e| *e = dest_value);
-                found = true;
-            }
-        });
-    });
-    seeds.iter().for_each(|seed| {
-        let next_val = *paths.get(seed).unwrap();
-        let mut found = false;
-        almanac.soil_to_fertilizer.iter().for_each(|rule| {
-            if (rule.source_range_start..rule.source_range_start + rule.range_length)
-                .contains(&next_val)
-            {
-                // figure out index in source_range, then translate over to dest_range_start
-                let dx = next_val - rule.source_range_start;
-                let dest_value = rule.dest_range_start + dx;
-                paths.entry(seed).and_modify(|e| *e = dest_value);
-                found = true;
-            }
-        });
-        if !found {
-            paths.entry(seed).and_modify(|e| *e = next_val);
-        }
-    });
-    seeds.iter().for_each(|seed| {
-        let next_val = *paths.get(seed).unwrap();
-        let mut found = false;
-        almanac.fertilizer_to_water.iter().for_each(|rule| {
-            if (rule.source_range_start..rule.source_range_start + rule.range_length)
-                .contains(&next_val)
-            {
-                // figure out index in source_range, then translate over to dest_range_start
-                let dx = next_val - rule.source_range_start;
-                let dest_value = rule.dest_range_start + dx;
-                paths.entry(seed).and_modify(|e| *e = dest_value);
-                found = true;
-            }
-        });
-        if !found {
-            paths.entry(seed).and_modify(|e| *e = next_val);
-        }
-    });
-    seeds.iter().for_each(|seed| {
-        let next_val = *paths.get(seed).unwrap();
-        let mut found = false;
-        almanac.water_to_light.iter().for_each(|rule| {
-            if (rule.source_range_start..rule.source_range_start + rule.range_length)
-                .contains(&next_val)
-            {
-                // figure out index in source_range, then translate over to dest_range_start
-                let dx = next_val - rule.source_range_start;
-                let dest_value = rule.dest_range_start + dx;
-                paths.entry(seed).and_modify(|e| *e = dest_value);
-                found = true;
-            }
-        });
-        if !found {
-            paths.entry(seed).and_modify(|e| *e = next_val);
-        }
-    });
-    seeds.iter().for_each(|seed| {
-        let next_val = *paths.get(seed).unwrap();
-        let mut found = false;
-        almanac.light_to_temperature.iter().for_each(|rule| {
-            if (rule.source_range_start..rule.source_range_start + rule.range_length)
-                .contains(&next_val)
-            {
-                // figure out index in source_range, then translate over to dest_range_start
-                let dx = next_val - rule.source_range_start;
-                let dest_value = rule.dest_range_start + dx;
-                paths.entry(seed).and_modify(|e| *e = dest_value);
-                found = true;
-            }
-        });
-        if !found {
-            paths.entry(seed).and_modify(|e| *e = next_val);
-        }
-    });
-    seeds.iter().for_each(|seed| {
-        let next_val = *paths.get(seed).unwrap();
-        let mut found = false;
-        almanac.temperature_to_humidity.iter().for_each(|rule| {
-            if (rule.source_range_start..rule.source_range_start + rule.range_length)
-                .contains(&next_val)
-            {
-                // figure out index in source_range, then translate over to dest_range_start
-                let dx = next_val - rule.source_range_start;
-                let dest_value = rule.dest_range_start + dx;
-                paths.entry(seed).and_modify(|e| *e = dest_value);
-                found = true;
-            }
-        });
-        if !found {
-            paths.entry(seed).and_modify(|e| *e = next_val);
-        }
-    });
-    seeds.iter().for_each(|seed| {
-        let next_val = *paths.get(seed).unwrap();
-        let mut found = false;
-        almanac.humidity_to_location.iter().for_each(|rule| {
-            if (rule.source_range_start..rule.source_range_start + rule.range_length)
-                .contains(&next_val)
-            {
-                // figure out index in source_range, then translate over to dest_range_start
-                let dx = next_val - rule.source_range_start;
-                let dest_value = rule.dest_range_start + dx;
-                paths.entry(seed).and_modify(|e| *e = dest_value);
-                found = true;
-            }
-        });
-        if !found {
-            paths.entry(seed).and_modify(|e| *e = next_val);
-        }
-    });
-
-    *paths.iter().map(|(_seed, path)| path).min().unwrap() as u32
+    let locations = almanac
+        .range_maps()
+        .iter()
+        .fold(seeds, |ranges, map| map.apply_set(&ranges));
+    locations.min()
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -183,6 +51,29 @@ pub struct Almanac {
     humidity_to_location: Vec<MappingRule>,
 }
 
+impl Almanac {
+    /// The mapping stages in order, from seed to location.
+    fn range_maps(&self) -> Vec<RangeMap<u64>> {
+        [
+            &self.seeds_to_soil,
+            &self.soil_to_fertilizer,
+            &self.fertilizer_to_water,
+            &self.water_to_light,
+            &self.light_to_temperature,
+            &self.temperature_to_humidity,
+            &self.humidity_to_location,
+        ]
+        .iter()
+        .map(|rules| {
+            rules
+                .iter()
+                .map(|r| (r.source_range_start, r.dest_range_start, r.range_length))
+                .collect()
+        })
+        .collect()
+    }
+}
+
 pub fn parse(input: &str) -> Almanac {
     let mut alm = Almanac::default();
     let mut dest = "";
@@ -241,6 +132,6 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(46));
     }
 }
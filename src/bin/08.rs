@@ -1,5 +1,9 @@
-use num::integer::lcm;
-use std::collections::BTreeMap;
+use advent_of_code::{
+    cycle,
+    intern::Interner,
+    math::{self, Congruence},
+};
+use itertools::Itertools;
 
 use nom::{
     branch::alt,
@@ -62,101 +66,134 @@ fn parse_directions(input: &str) -> IResult<&str, Vec<Dir>> {
     }))(input)
 }
 
-fn parse(input: &str) -> (Vec<Dir>, BTreeMap<&str, (&str, &str)>) {
+/// The map as an adjacency table, indexed by interned node id.
+struct Network {
+    nodes: Interner,
+    /// (left, right) for every node
+    targets: Vec<(u32, u32)>,
+}
+
+impl Network {
+    fn next(&self, node: u32, dir: &Dir) -> u32 {
+        let (left, right) = self.targets[node as usize];
+        match dir {
+            Dir::L => left,
+            Dir::R => right,
+        }
+    }
+}
+
+fn parse(input: &str) -> (Vec<Dir>, Network) {
     let (input, directions) = parse_directions(input).unwrap();
-    let (_, nodes) = parse_nodes(input).unwrap();
-    let nodes = nodes
+    let (_, lines) = parse_nodes(input).unwrap();
+    let mut nodes = Interner::new();
+    let lines = lines
         .iter()
-        .fold(BTreeMap::<&str, _>::new(), |mut acc, this_node| {
-            acc.insert(this_node.0, (this_node.1, this_node.2));
-            acc
-        });
-    (directions, nodes)
+        .map(|(node, left, right)| (nodes.intern(node), nodes.intern(left), nodes.intern(right)))
+        .collect_vec();
+    let mut targets = vec![(0, 0); nodes.len()];
+    for (node, left, right) in lines {
+        targets[node as usize] = (left, right);
+    }
+    (directions, Network { nodes, targets })
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let (directions, nodes) = parse(input);
-    let mut curr = "AAA";
+    let (directions, network) = parse(input);
+    let mut curr = network.nodes.get("AAA")?;
+    let end = network.nodes.get("ZZZ")?;
     let mut steps = 0;
     let mut directions = directions.iter().cycle();
     loop {
         steps += 1;
         let dir = directions.next().unwrap();
-        curr = match dir {
-            Dir::L => nodes.get(curr).unwrap().0,
-            Dir::R => nodes.get(curr).unwrap().1,
-        };
-        // println!("went {:?} to arrive at {curr}", dir);
-        if curr == "ZZZ" {
+        curr = network.next(curr, dir);
+        advent_of_code::trace!("went {:?} to arrive at {}", dir, network.nodes.name(curr));
+        if curr == end {
             break;
         }
     }
     Some(steps)
 }
 
-// Detect cycles for each of the starting nodes, then figure out least-common-multiple of all of them
-pub fn part_two(input: &str) -> Option<u128> {
-    let (directions, nodes) = parse(input);
-    let starting_nodes: Vec<_> = nodes
-        .keys()
-        .cloned()
-        .filter(|n| n.ends_with("A"))
-        // .take(2)
-        .collect();
-    let mut cycles = vec![];
-
-    let direction_loop_size = directions.len();
-    println!("directions repeat every {:?} steps", direction_loop_size);
-    starting_nodes.iter().for_each(|node| {
-        let mut directions = directions.iter().enumerate().cycle();
-        let mut steps = 0;
-        let orig = node;
-        let mut curr = node;
-        let mut first = None;
-        // print!("{:?} ", curr);
-        let mut cycle_pos = None;
-        loop {
-            let (dir_pos, dir) = directions.next().unwrap();
-            let target = &nodes.get(curr).unwrap();
-            curr = match dir {
-                Dir::L => &target.0,
-                Dir::R => &target.1,
-            };
-            // print!(" -({:?})-> {:?}", &dir, &curr);
-
-            if first.is_none() {
-                first = Some(curr);
-            } else {
-                steps += 1;
-                if curr == first.unwrap() {
-                    // FIXME it isn't enough to be back at the original node, we also have to be at the same place in the directions loop?
-                    if cycle_pos.is_none() {
-                        // println!("found first cycle for {orig}, {steps} steps, but pos in dir vec is {}", dir_pos);
-                        cycle_pos = Some(dir_pos);
-                    } else {
-                        // println!("found cycle for {orig}, {steps} steps, but pos in dir vec is {} (adjusted: {})", dir_pos, (dir_pos % direction_loop_size));
-                        if dir_pos % direction_loop_size == cycle_pos.unwrap() {
-                            println!(
-                                "Success? {:?} {:?} after {:?} steps",
-                                cycle_pos.unwrap(),
-                                dir_pos,
-                                steps
-                            );
-                            cycles.push(steps);
-                            break;
-                        }
-                    }
-                }
-            }
+/// Steps at which a ghost stands on a `Z` node: once for hits before its cycle starts, and repeatedly for hits inside it.
+struct Arrivals {
+    once: Vec<usize>,
+    repeating: Vec<Congruence>,
+    cycle_start: usize,
+}
+
+impl Arrivals {
+    fn is_at_z(&self, steps: usize) -> bool {
+        if steps < self.cycle_start {
+            self.once.contains(&steps)
+        } else {
+            self.repeating
+                .iter()
+                .any(|c| steps as i64 % c.modulus == c.residue)
         }
+    }
+}
+
+fn arrivals(start: u32, directions: &[Dir], network: &Network, is_end: &[bool]) -> Arrivals {
+    // the state has to include the position in the directions, being back on a node is not enough for a cycle
+    let (history, cycle) = cycle::find((start, 0), |&(node, dir_pos)| {
+        (
+            network.next(node, &directions[dir_pos]),
+            (dir_pos + 1) % directions.len(),
+        )
     });
 
-    dbg!(&cycles);
+    let (once, repeating): (Vec<_>, Vec<_>) = history
+        .iter()
+        .enumerate()
+        .filter(|(_, (node, _))| is_end[*node as usize])
+        .map(|(steps, _)| steps)
+        .partition(|steps| *steps < cycle.start);
+
+    Arrivals {
+        once,
+        repeating: repeating
+            .into_iter()
+            .map(|steps| Congruence::new(steps as i64, cycle.period as i64))
+            .collect(),
+        cycle_start: cycle.start,
+    }
+}
+
+// Detect cycles for each of the starting nodes, then line them up with the chinese remainder theorem
+pub fn part_two(input: &str) -> Option<u64> {
+    let (directions, network) = parse(input);
+    let is_end = network
+        .nodes
+        .iter()
+        .map(|(_, name)| name.ends_with('Z'))
+        .collect_vec();
+    let ghosts: Vec<_> = network
+        .nodes
+        .iter()
+        .filter(|(_, name)| name.ends_with('A'))
+        .map(|(node, _)| arrivals(node, &directions, &network, &is_end))
+        .collect();
+
+    advent_of_code::debug!(
+        "cycles: {:?}",
+        ghosts.iter().map(|g| &g.repeating).collect::<Vec<_>>()
+    );
 
-    // u128 max is: 340282366920938463463374607431768211455
-    // and coming up with: 1858646397880 so well within bounds
-    // so maybe something wrong with cycle detection? (correct answer is 14265111103729)
-    Some(cycles.into_iter().fold(1, |acc, n| lcm(acc, n)))
+    // all ghosts are in their cycles from here on, before that they might meet at a one-off arrival
+    let all_cycling = ghosts.iter().map(|g| g.cycle_start).max()?;
+    if let Some(steps) = (0..all_cycling).find(|steps| ghosts.iter().all(|g| g.is_at_z(*steps))) {
+        return Some(steps as u64);
+    }
+
+    ghosts
+        .iter()
+        .map(|g| g.repeating.clone())
+        .multi_cartesian_product()
+        .filter_map(|combination| math::crt(combination).ok())
+        .map(|solution| solution.first_at_least(all_cycling as i64) as u64)
+        .min()
 }
 
 #[cfg(test)]
@@ -1,33 +1,26 @@
 use std::ops::Add;
 
-use itertools::Itertools;
+use advent_of_code::geom::{Direction4, Point2};
+use advent_of_code::polygon::Polygon;
 
 advent_of_code::solution!(10);
 
-#[derive(Debug, Clone, Copy)]
-enum Heading {
-    N,
-    S,
-    E,
-    W,
-}
-
 #[derive(Debug, Clone)]
 struct Traveler {
-    heading: Heading,
+    heading: Direction4,
     current_pos: Point,
     path_taken: Vec<Point>,
 }
 
 impl Traveler {
-    fn new(heading: Heading, pos: Point) -> Self {
+    fn new(heading: Direction4, pos: Point) -> Self {
         Traveler {
             heading,
             current_pos: pos,
             path_taken: vec![pos.clone()],
         }
     }
-    fn move_to(&mut self, delta: Point, heading: Heading) {
+    fn move_to(&mut self, delta: Point, heading: Direction4) {
         self.heading = heading;
         self.current_pos = self.current_pos + delta;
         self.path_taken.push(self.current_pos);
@@ -46,53 +39,53 @@ impl Add for Point {
     }
 }
 
-fn next_step(heading: &Heading, symbol: &char) -> (Heading, Point) {
+fn next_step(heading: &Direction4, symbol: &char) -> (Direction4, Point) {
     match symbol {
         '|' => {
             // N,S
             match heading {
-                Heading::N => (Heading::N, Point(0, -1)),
-                Heading::S => (Heading::S, Point(0, 1)),
+                Direction4::N => (Direction4::N, Point(0, -1)),
+                Direction4::S => (Direction4::S, Point(0, 1)),
                 _ => unreachable!(),
             }
         }
         '-' => {
             // E,W
             match heading {
-                Heading::E => (Heading::E, Point(1, 0)),
-                Heading::W => (Heading::W, Point(-1, 0)),
+                Direction4::E => (Direction4::E, Point(1, 0)),
+                Direction4::W => (Direction4::W, Point(-1, 0)),
                 _ => unreachable!(),
             }
         }
         'L' => {
             // N, E
             match heading {
-                Heading::W => (Heading::N, Point(0, -1)),
-                Heading::S => (Heading::E, Point(1, 0)),
+                Direction4::W => (Direction4::N, Point(0, -1)),
+                Direction4::S => (Direction4::E, Point(1, 0)),
                 _ => unreachable!(),
             }
         }
         'J' => {
             // N, W
             match heading {
-                Heading::E => (Heading::N, Point(0, -1)),
-                Heading::S => (Heading::W, Point(-1, 0)),
+                Direction4::E => (Direction4::N, Point(0, -1)),
+                Direction4::S => (Direction4::W, Point(-1, 0)),
                 _ => unreachable!(),
             }
         }
         '7' => {
             // S, W
             match heading {
-                Heading::E => (Heading::S, Point(0, 1)),
-                Heading::N => (Heading::W, Point(-1, 0)),
+                Direction4::E => (Direction4::S, Point(0, 1)),
+                Direction4::N => (Direction4::W, Point(-1, 0)),
                 _ => unreachable!(),
             }
         }
         'F' => {
             // S, E
             match heading {
-                Heading::W => (Heading::S, Point(0, 1)),
-                Heading::N => (Heading::E, Point(1, 0)),
+                Direction4::W => (Direction4::S, Point(0, 1)),
+                Direction4::N => (Direction4::E, Point(1, 0)),
                 _ => unreachable!(),
             }
         }
@@ -116,33 +109,6 @@ fn parse(input: &str) -> (Point, Vec<Vec<char>>) {
     (starting_point, map)
 }
 
-fn simplify_map(map: &Vec<Vec<char>>, path: &Vec<Point>) -> Vec<Vec<char>> {
-    map.iter()
-        .enumerate()
-        .map(|(y, row)| {
-            row.iter()
-                .enumerate()
-                .map(|(x, cell)| {
-                    if path.contains(&Point(x as i32, y as i32)) {
-                        *cell
-                    } else {
-                        '.'
-                    }
-                })
-                .collect_vec()
-        })
-        .collect_vec()
-}
-
-fn print_map(map: &Vec<Vec<char>>) {
-    map.iter().for_each(|row| {
-        row.iter().for_each(|cell| {
-            print!("{}", cell);
-        });
-        println!("")
-    })
-}
-
 // if we find the starting point and navigate from there in both directions, where those meet up should be furthest point
 pub fn part_one(input: &str) -> Option<u32> {
     let (starting_pos, map) = parse(input);
@@ -155,21 +121,33 @@ pub fn part_one(input: &str) -> Option<u32> {
 
     if y > 0 && (map[y - 1][x] == '|' || map[y - 1][x] == '7' || map[y - 1][x] == 'F') {
         // north path
-        travelers.push(Traveler::new(Heading::N, Point(x as i32, (y - 1) as i32)));
+        travelers.push(Traveler::new(
+            Direction4::N,
+            Point(x as i32, (y - 1) as i32),
+        ));
     }
     if x > 0 && (map[y][x - 1] == '-' || map[y][x - 1] == 'L' || map[y][x - 1] == 'F') {
         // west path
-        travelers.push(Traveler::new(Heading::W, Point((x - 1) as i32, y as i32)));
+        travelers.push(Traveler::new(
+            Direction4::W,
+            Point((x - 1) as i32, y as i32),
+        ));
     }
     if y < map.len() - 1 && (map[y + 1][x] == '|' || map[y + 1][x] == 'L' || map[y + 1][x] == 'J') {
         // south path
-        travelers.push(Traveler::new(Heading::S, Point(x as i32, (y + 1) as i32)));
+        travelers.push(Traveler::new(
+            Direction4::S,
+            Point(x as i32, (y + 1) as i32),
+        ));
     }
     if x < map[0].len() - 1
         && (map[y][x + 1] == '-' || map[y][x + 1] == 'J' || map[y][x + 1] == '7')
     {
         // east path
-        travelers.push(Traveler::new(Heading::E, Point((x + 1) as i32, y as i32)));
+        travelers.push(Traveler::new(
+            Direction4::E,
+            Point((x + 1) as i32, y as i32),
+        ));
     }
     // now just run around the pipes until the two travelers meet at same spot
     // don't need to do any bounds checking since the pipes must be connected
@@ -208,21 +186,33 @@ pub fn part_two(input: &str) -> Option<u32> {
 
     if y > 0 && (map[y - 1][x] == '|' || map[y - 1][x] == '7' || map[y - 1][x] == 'F') {
         // north path
-        travelers.push(Traveler::new(Heading::N, Point(x as i32, (y - 1) as i32)));
+        travelers.push(Traveler::new(
+            Direction4::N,
+            Point(x as i32, (y - 1) as i32),
+        ));
     }
     if x > 0 && (map[y][x - 1] == '-' || map[y][x - 1] == 'L' || map[y][x - 1] == 'F') {
         // west path
-        travelers.push(Traveler::new(Heading::W, Point((x - 1) as i32, y as i32)));
+        travelers.push(Traveler::new(
+            Direction4::W,
+            Point((x - 1) as i32, y as i32),
+        ));
     }
     if y < map.len() - 1 && (map[y + 1][x] == '|' || map[y + 1][x] == 'L' || map[y + 1][x] == 'J') {
         // south path
-        travelers.push(Traveler::new(Heading::S, Point(x as i32, (y + 1) as i32)));
+        travelers.push(Traveler::new(
+            Direction4::S,
+            Point(x as i32, (y + 1) as i32),
+        ));
     }
     if x < map[0].len() - 1
         && (map[y][x + 1] == '-' || map[y][x + 1] == 'J' || map[y][x + 1] == '7')
     {
         // east path
-        travelers.push(Traveler::new(Heading::E, Point((x + 1) as i32, y as i32)));
+        travelers.push(Traveler::new(
+            Direction4::E,
+            Point((x + 1) as i32, y as i32),
+        ));
     }
     // now just run around the pipes until the two travelers meet at same spot
     // don't need to do any bounds checking since the pipes must be connected
@@ -233,19 +223,17 @@ pub fn part_two(input: &str) -> Option<u32> {
             panic!("loop completed without encountering t2, fail!");
         }
         if t1.current_pos == t2.current_pos {
-            // now we know the entire loop path
+            // now we know the entire loop path: out along t1, then back along t2 (both end at the meeting point)
             let mut path = vec![starting_pos]; // don't forget to put starting point back in
             path.extend(&t1.path_taken);
-            path.extend(&t2.path_taken);
-
-            // can get rid of other tokens
-            let simplified = simplify_map(&map, &path);
-            print_map(&simplified);
+            path.extend(t2.path_taken.iter().rev().skip(1));
 
-            todo!(
-                "and here's where we need to test the points to see if they reach outside the loop"
-            );
-            return Some(t1.path_taken.len() as u32);
+            // every tile of the loop is a vertex, so Pick's theorem counts the tiles enclosed by it
+            let pipe_loop: Polygon = path
+                .iter()
+                .map(|p| Point2::new(p.0 as i64, p.1 as i64))
+                .collect();
+            return Some(pipe_loop.interior_points() as u32);
         }
         let t1next = next_step(
             &t1.heading,
@@ -285,6 +273,6 @@ L--J.L7...LJS7F-7L7.
 ....FJL-7.||.||||...
 ....L---J.LJ.LJLJ...",
         );
-        assert_eq!(result, Some(10));
+        assert_eq!(result, Some(8));
     }
 }
@@ -1,11 +1,12 @@
 use std::collections::BTreeSet;
 
+use advent_of_code::geom::Point2;
 use itertools::Itertools;
 
 advent_of_code::solution!(11);
 
 fn manhattan(a: &(u32, u32), b: &(u32, u32)) -> i64 {
-    i64::abs(b.0 as i64 - a.0 as i64) + i64::abs(b.1 as i64 - a.1 as i64)
+    Point2::new(a.0 as i64, a.1 as i64).manhattan(&Point2::new(b.0 as i64, b.1 as i64))
 }
 
 fn parse(input: &str) -> Vec<(u32, u32)> {
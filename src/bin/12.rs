@@ -1,5 +1,5 @@
-use std::vec;
-
+use advent_of_code::memo::Memo;
+use advent_of_code::par;
 use itertools::Itertools;
 use regex::Regex;
 
@@ -9,19 +9,17 @@ advent_of_code::solution!(12);
 // since there are only two possiblities for each position with a question mark...
 pub fn part_one(input: &str) -> Option<u32> {
     let data = parse(input);
-    let a: u32 = data
-        .iter()
-        .map(|(positions, validation)| (resolve_one(&positions), build_regex(validation)))
-        .map(|(inputs, validation_regex)| {
-            let re = Regex::new(&validation_regex).unwrap();
-            inputs.iter().filter(|i| re.is_match(*i)).count() as u32
-        })
-        .sum();
+    let a: u32 = par::par_sum(&data, |(positions, validation)| {
+        let inputs = resolve_one(positions);
+        let re = Regex::new(&build_regex(validation)).unwrap();
+        inputs.iter().filter(|i| re.is_match(i)).count() as u32
+    });
     Some(a)
 }
 
 // lol, not a chance to brute force this one - will need to think it through...
-pub fn part_two(input: &str) -> Option<u32> {
+// count the arrangements from each (position, group) onwards instead, and cache them per line
+pub fn part_two(input: &str) -> Option<u64> {
     let orig = parse(input);
 
     let data = orig
@@ -30,12 +28,55 @@ pub fn part_two(input: &str) -> Option<u32> {
             (
                 // is there a way to repeat with a delimiter? this works though
                 format!(r"{}?{}?{}?{}?{}", &line, &line, &line, &line, &line),
-                rule.iter().cycle().take(rule.len() * 5).collect::<Vec<_>>(),
+                rule.iter()
+                    .cycle()
+                    .take(rule.len() * 5)
+                    .map(|n| *n as usize)
+                    .collect::<Vec<_>>(),
             )
         })
         .collect_vec();
-    todo!();
-    None
+
+    let mut memo = Memo::new();
+    let total = data
+        .iter()
+        .map(|(line, rule)| {
+            memo.clear();
+            arrangements(&mut memo, line.as_bytes(), rule, 0, 0)
+        })
+        .sum();
+    memo.log_stats("arrangements");
+    Some(total)
+}
+
+// the number of ways to place groups[g..] in springs[i..]
+fn arrangements(
+    memo: &mut Memo<(usize, usize), u64>,
+    springs: &[u8],
+    groups: &[usize],
+    i: usize,
+    g: usize,
+) -> u64 {
+    if i >= springs.len() {
+        return u64::from(g == groups.len());
+    }
+    memo.get((i, g), |memo| {
+        let mut count = 0;
+        if springs[i] != b'#' {
+            count += arrangements(memo, springs, groups, i + 1, g);
+        }
+        if springs[i] != b'.' && g < groups.len() {
+            let end = i + groups[g];
+            // the group has to fit, and be followed by an operational spring (or the end)
+            if end <= springs.len()
+                && !springs[i..end].contains(&b'.')
+                && springs.get(end) != Some(&b'#')
+            {
+                count += arrangements(memo, springs, groups, end + 1, g + 1);
+            }
+        }
+        count
+    })
 }
 
 fn build_regex(rule: &Vec<u32>) -> String {
@@ -103,7 +144,7 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(525152));
     }
 
     #[test]
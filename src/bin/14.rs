@@ -1,119 +1,73 @@
-use std::collections::BTreeMap;
-
-use itertools::Itertools;
+use advent_of_code::bitgrid::BitGrid;
+use advent_of_code::geom::Direction4;
+use advent_of_code::sim::{Driver, Simulation};
+use advent_of_code::viz::{Cell, Frame, Rgb};
 
 advent_of_code::solution!(14);
 
-/// (num_cols, num_rows, dish)
-fn parse(input: &str) -> (usize, usize, Vec<char>) {
-    (
-        input.find("\n").unwrap(),
-        input.lines().count(),
-        input.chars().filter(|c| !c.is_whitespace()).collect_vec(),
-    )
+#[derive(Debug, Clone)]
+struct Platform {
+    rocks: BitGrid,
+    cubes: BitGrid,
 }
 
-fn score(dish: &Vec<char>, num_rows: usize) -> u32 {
-    let score = dish.iter().enumerate().fold(0, |mut acc, (idx, ch)| {
-        if *ch == 'O' {
-            acc += num_rows - (idx / num_rows)
-        }
-        acc
-    });
-    score as u32
+fn parse(input: &str) -> Platform {
+    Platform {
+        rocks: BitGrid::parse(input, |c| c == 'O'),
+        cubes: BitGrid::parse(input, |c| c == '#'),
+    }
 }
 
-// only going to work for a square matrix, which we have, yay.
-pub fn rot_90(dish: &Vec<char>, width: usize) -> Vec<char> {
-    let mut rot = vec![' '; dish.len()];
-    dish.iter().enumerate().for_each(|(n, c)| {
-        let level = width - 1 - (n / width);
-        let col = n % width;
-        let target = (col * width) + level;
-        rot[target] = *c;
-    });
-    // println!("{}", &dish.iter().join(""));
-    rot.to_owned()
+/// a step is a spin cycle: tilt north, west, south and east
+impl Simulation for Platform {
+    type Key = BitGrid;
+
+    fn step(&mut self) {
+        for direction in [Direction4::N, Direction4::W, Direction4::S, Direction4::E] {
+            self.rocks.roll(direction, &self.cubes);
+        }
+    }
+
+    fn state_key(&self) -> BitGrid {
+        self.rocks.clone()
+    }
+
+    fn render(&self) -> Frame {
+        Frame::from_fn(self.rocks.width(), self.rocks.height(), |x, y| {
+            let (x, y) = (x as isize, y as isize);
+            if self.rocks.get(x, y) {
+                Cell::new('O').fg(Rgb::YELLOW).bold()
+            } else if self.cubes.get(x, y) {
+                Cell::new('#').fg(Rgb::GRAY)
+            } else {
+                Cell::new('.')
+            }
+        })
+    }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (num_cols, num_rows, mut dish) = parse(input);
-    let dish = tilt_north(&mut dish, num_cols);
-    Some(score(&dish, num_rows))
+/// the load of a rock is its distance from the south edge, counting its own row
+fn score(rocks: &BitGrid) -> u32 {
+    rocks
+        .iter_ones()
+        .map(|(_, y)| (rocks.height() - y) as u32)
+        .sum()
 }
 
-pub fn tilt_north(dish: &mut Vec<char>, num_cols: usize) -> Vec<char> {
-    (num_cols..dish.len()).for_each(|n| {
-        // try move each dish[n] left
-        let mut this_n = n;
-        if dish[n] == 'O' {
-            loop {
-                if this_n < num_cols {
-                    break;
-                }
-                let next_n = this_n - num_cols;
-                if dish[next_n] == '.' {
-                    dish[next_n] = 'O';
-                    dish[this_n] = '.';
-                } else {
-                    break;
-                }
-                if this_n < num_cols {
-                    break;
-                }
-                this_n = this_n - num_cols;
-            }
-        }
-    });
-    dish.to_owned()
+pub fn part_one(input: &str) -> Option<u32> {
+    let mut platform = parse(input);
+    platform.rocks.roll(Direction4::N, &platform.cubes);
+    Some(score(&platform.rocks))
 }
 
 // we'll have to detect a cycle, there's no way we need to run this 1_000_000_000 times
 pub fn part_two(input: &str) -> Option<u32> {
-    let (num_cols, num_rows, dish) = parse(input);
-
-    let mut dish = dish.to_owned();
-    let mut times = 1;
-    let mut cycle = 0;
-    let mut m = BTreeMap::new();
-    let mut answer = None;
-    loop {
-        let mut tilted = &tilt_north(&mut dish, num_cols);
-        let rot = rot_90(&mut tilted, num_cols);
-        if times % 4 == 0 {
-            let s = rot.iter().join("");
-            cycle += 1;
-            // println!("cycle {cycle}: {s}");
-            if m.contains_key(&s) {
-                let last_seen = m.get(&s).unwrap();
-                let repeating_cycle = cycle - last_seen;
-                // println!("Cycle of cycles detected in {cycle}, prev was {last_seen}! So, every {repeating_cycle} cycles");
-                // then we need to grab the nth cycle since the billionth didn't fall neatly
-                let offset = (1_000_000_000 - *last_seen as usize) % repeating_cycle as usize;
-                // println!("We need the output from {offset}");
-                // should be quick iteration since we stopped after the first cycle was detected, but must be a better way..?
-                answer = m
-                    .iter()
-                    .filter_map(|(k, v)| {
-                        if *v == offset + last_seen {
-                            Some(k)
-                        } else {
-                            None
-                        }
-                    })
-                    .at_most_one()
-                    .unwrap();
-                // dbg!(&answer);
-                break;
-            } else {
-                m.insert(s, cycle);
-            }
-        }
-        times = times + 1;
-        dish = rot;
+    let mut driver = Driver::new(parse(input)).detect_cycles().animate();
+    driver.run(1_000_000_000);
+    if let Some(cycle) = driver.cycle() {
+        advent_of_code::debug!("{cycle:?}");
     }
-    let dish = answer.unwrap().chars().collect_vec();
-    Some(score(&dish, num_rows))
+    Some(score(&driver.finish().rocks))
 }
 
 #[cfg(test)]
@@ -133,16 +87,13 @@ mod tests {
     }
 
     #[test]
-    fn test_rotation() {
-        let result = rot_90(
-            &vec![
-                '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g',
-            ],
-            4,
+    fn test_spin_cycle() {
+        let mut platform = parse(&advent_of_code::template::read_file("examples", DAY));
+        platform.step();
+        let expected = BitGrid::parse(
+            ".....#....\n....#...O#\n...OO##...\n.OO#......\n.....OOO#.\n.O#...O#.#\n....O....#\n......OOOO\n#...O###..\n#..OO#....",
+            |c| c == 'O',
         );
-        assert_eq!(
-            result,
-            vec!['d', '9', '5', '1', 'e', 'a', '6', '2', 'f', 'b', '7', '3', 'g', 'c', '8', '4']
-        )
+        assert_eq!(platform.rocks, expected);
     }
 }
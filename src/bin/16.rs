@@ -1,5 +1,9 @@
 use std::collections::HashSet;
 
+use advent_of_code::geom::Direction4;
+use advent_of_code::par;
+use advent_of_code::sim::{Driver, Simulation};
+use advent_of_code::viz::{Cell, Frame, Rgb, Style};
 use itertools::Itertools;
 
 advent_of_code::solution!(16);
@@ -15,29 +19,16 @@ enum Component {
 }
 
 #[derive(Debug, Clone)]
-enum Heading {
-    R,
-    L,
-    U,
-    D,
-}
-
-#[derive(Debug)]
 struct Beam {
     /// zero-based position in the layout
     pos: (isize, isize),
-    heading: Heading,
+    heading: Direction4,
 }
 
 impl Beam {
     fn next_pos(&self) -> (isize, isize) {
-        let (dx, dy) = match self.heading {
-            Heading::R => (1, 0),
-            Heading::D => (0, 1),
-            Heading::L => (-1, 0),
-            Heading::U => (0, -1),
-        };
-        (self.pos.0 + dx, self.pos.1 + dy)
+        let delta = self.heading.delta::<isize>();
+        (self.pos.0 + delta.x, self.pos.1 + delta.y)
     }
 }
 
@@ -59,51 +50,42 @@ fn parse(input: &str) -> Vec<Vec<Component>> {
         .collect_vec()
 }
 
-#[allow(dead_code)]
-fn display(layout: &Vec<Vec<Component>>, energized: &HashSet<(isize, isize)>) {
-    layout.iter().enumerate().for_each(|(y, row)| {
-        row.iter().enumerate().for_each(|(x, component)| {
-            if energized.contains(&(x as isize, y as isize)) {
-                print!("#");
-            } else {
-                match component {
-                    Component::RightLeaningMirror => print!("\\"),
-                    Component::LeftLeaningMirror => print!("/"),
-                    Component::HorizontalSplitter => print!("-"),
-                    Component::VerticalSplitter => print!("|"),
-                    Component::EmptySpace => print!("."),
-                }
-            }
-        });
-        println!();
-    });
+/// the beams moving through the layout, and the tiles they've energized so far
+#[derive(Debug, Clone)]
+struct Contraption<'a> {
+    layout: &'a [Vec<Component>],
+    beams: Vec<Beam>,
+    energized: HashSet<(isize, isize)>,
 }
 
-fn bounce_around(
-    layout: &Vec<Vec<Component>>,
-    starting_pos: (isize, isize),
-    heading: Heading,
-) -> u32 {
-    let mut beams = vec![Beam {
-        pos: starting_pos,
-        heading,
-    }];
-
-    let mut energized = HashSet::new();
-    energized.insert(beams[0].pos);
-    let mut safety = 0;
-    loop {
-        // hopefully there won't be any cycles
-        if beams.is_empty() {
-            // println!("All cells energized and done");
-            break;
+impl<'a> Contraption<'a> {
+    fn new(
+        layout: &'a [Vec<Component>],
+        starting_pos: (isize, isize),
+        heading: Direction4,
+    ) -> Self {
+        Self {
+            layout,
+            beams: vec![Beam {
+                pos: starting_pos,
+                heading,
+            }],
+            energized: HashSet::from([starting_pos]),
         }
+    }
+}
+
+/// a step moves every beam by one tile
+impl Simulation for Contraption<'_> {
+    type Key = Vec<((isize, isize), Direction4)>;
+
+    fn step(&mut self) {
         let mut exiting = vec![];
         let mut entering = vec![];
-        beams.iter_mut().for_each(|beam| {
-            if beam.pos.0 > (layout[0].len() - 1) as isize
+        self.beams.iter_mut().for_each(|beam| {
+            if beam.pos.0 > (self.layout[0].len() - 1) as isize
                 || beam.pos.0 < 0
-                || beam.pos.1 > (layout.len() - 1) as isize
+                || beam.pos.1 > (self.layout.len() - 1) as isize
                 || beam.pos.1 < 0
             {
                 // println!("beam {idx} exited");
@@ -112,125 +94,148 @@ fn bounce_around(
                 // mark our spot
                 let to_mark = (beam.pos.0, beam.pos.1);
                 match (
-                    &layout[beam.pos.1 as usize][beam.pos.0 as usize],
+                    &self.layout[beam.pos.1 as usize][beam.pos.0 as usize],
                     &beam.heading,
                 ) {
-                    (Component::HorizontalSplitter, Heading::U)
-                    | (Component::HorizontalSplitter, Heading::D) => {
+                    (Component::HorizontalSplitter, Direction4::N)
+                    | (Component::HorizontalSplitter, Direction4::S) => {
                         // split into two beams, if it's not already off the map, and it hasn't already been energized, since that just
                         // makes a cycle
-                        if (beam.pos.0 as usize) < layout.len() - 1 && !energized.contains(&to_mark)
+                        if (beam.pos.0 as usize) < self.layout.len() - 1
+                            && !self.energized.contains(&to_mark)
                         {
                             entering.push(Beam {
                                 pos: (beam.pos.0 + 1, beam.pos.1),
-                                heading: Heading::R,
+                                heading: Direction4::E,
                             });
                         }
                         // old beam is redirected
-                        beam.heading = Heading::L;
+                        beam.heading = Direction4::W;
                         beam.pos = (beam.pos.0 - 1, beam.pos.1);
                     }
-                    (Component::VerticalSplitter, Heading::L)
-                    | (Component::VerticalSplitter, Heading::R) => {
+                    (Component::VerticalSplitter, Direction4::W)
+                    | (Component::VerticalSplitter, Direction4::E) => {
                         // split into two beams, if it's not already off the map, and it hasn't already been energized, since that just
                         // makes a cycle
-                        if (beam.pos.1 as usize) < layout.len() - 1 && !energized.contains(&to_mark)
+                        if (beam.pos.1 as usize) < self.layout.len() - 1
+                            && !self.energized.contains(&to_mark)
                         {
                             entering.push(Beam {
                                 pos: (beam.pos.0, beam.pos.1 + 1),
-                                heading: Heading::D,
+                                heading: Direction4::S,
                             });
                         }
                         // old beam is redirected
-                        beam.heading = Heading::U;
+                        beam.heading = Direction4::N;
                         beam.pos = (beam.pos.0, beam.pos.1 - 1);
                     }
-                    (Component::LeftLeaningMirror, Heading::R) => {
-                        beam.heading = Heading::U;
+                    (Component::LeftLeaningMirror, Direction4::E) => {
+                        beam.heading = Direction4::N;
                         beam.pos = (beam.pos.0, beam.pos.1 - 1);
                     }
-                    (Component::LeftLeaningMirror, Heading::U) => {
-                        beam.heading = Heading::R;
+                    (Component::LeftLeaningMirror, Direction4::N) => {
+                        beam.heading = Direction4::E;
                         beam.pos = (beam.pos.0 + 1, beam.pos.1);
                     }
-                    (Component::LeftLeaningMirror, Heading::D) => {
-                        beam.heading = Heading::L;
+                    (Component::LeftLeaningMirror, Direction4::S) => {
+                        beam.heading = Direction4::W;
                         beam.pos = (beam.pos.0 - 1, beam.pos.1);
                     }
-                    (Component::LeftLeaningMirror, Heading::L) => {
-                        beam.heading = Heading::D;
+                    (Component::LeftLeaningMirror, Direction4::W) => {
+                        beam.heading = Direction4::S;
                         beam.pos = (beam.pos.0, beam.pos.1 + 1);
                     }
-                    (Component::RightLeaningMirror, Heading::R) => {
-                        beam.heading = Heading::D;
+                    (Component::RightLeaningMirror, Direction4::E) => {
+                        beam.heading = Direction4::S;
                         beam.pos = (beam.pos.0, beam.pos.1 + 1);
                     }
-                    (Component::RightLeaningMirror, Heading::U) => {
-                        beam.heading = Heading::L;
+                    (Component::RightLeaningMirror, Direction4::N) => {
+                        beam.heading = Direction4::W;
                         beam.pos = (beam.pos.0 - 1, beam.pos.1);
                     }
-                    (Component::RightLeaningMirror, Heading::D) => {
-                        beam.heading = Heading::R;
+                    (Component::RightLeaningMirror, Direction4::S) => {
+                        beam.heading = Direction4::E;
                         beam.pos = (beam.pos.0 + 1, beam.pos.1);
                     }
-                    (Component::RightLeaningMirror, Heading::L) => {
-                        beam.heading = Heading::U;
+                    (Component::RightLeaningMirror, Direction4::W) => {
+                        beam.heading = Direction4::N;
                         beam.pos = (beam.pos.0, beam.pos.1 - 1);
                     }
                     _ => {
                         beam.pos = beam.next_pos();
                     }
                 }
-                energized.insert(to_mark);
+                self.energized.insert(to_mark);
             }
-            safety += 1;
         });
-        // display(&layout, &beams);
-        beams.retain(|x| !exiting.contains(&x.pos));
-        beams.append(&mut entering);
-        // turns out we don't need this check, since we're eliminating beams spawning multiple times from the splitters
-
-        // if safety % 100 == 0 {
-        //     // every few rounds, see if we reached a steady state... will this work?
-        //     if last_energized == energized.len() {
-        //         println!("Think we reached a steady state, let's quit!");
-        //         break;
-        //     } else {
-        //         last_energized = energized.len();
-        //     }
-        // }
+        self.beams.retain(|x| !exiting.contains(&x.pos));
+        self.beams.append(&mut entering);
     }
-    // display(&layout, &energized);
-    energized.len() as u32
+
+    fn state_key(&self) -> Self::Key {
+        self.beams
+            .iter()
+            .map(|beam| (beam.pos, beam.heading))
+            .collect()
+    }
+
+    fn render(&self) -> Frame {
+        let layout = self.layout;
+        let mut frame = Frame::from_fn(layout[0].len(), layout.len(), |x, y| {
+            Cell::new(match layout[y][x] {
+                Component::RightLeaningMirror => '\\',
+                Component::LeftLeaningMirror => '/',
+                Component::HorizontalSplitter => '-',
+                Component::VerticalSplitter => '|',
+                Component::EmptySpace => '.',
+            })
+        });
+        frame.highlight(
+            self.energized.iter().copied(),
+            Style::default().fg(Rgb::BLACK).bg(Rgb::YELLOW),
+        );
+        frame.highlight(
+            self.beams.iter().map(|beam| beam.pos),
+            Style::default().bg(Rgb::RED).bold(),
+        );
+        frame
+    }
+}
+
+fn bounce_around(
+    layout: &[Vec<Component>],
+    starting_pos: (isize, isize),
+    heading: Direction4,
+    animate: bool,
+) -> HashSet<(isize, isize)> {
+    let mut driver = Driver::new(Contraption::new(layout, starting_pos, heading));
+    if animate {
+        driver = driver.animate();
+    }
+    // hopefully there won't be any cycles
+    driver.run_until(|contraption| contraption.beams.is_empty());
+    driver.finish().energized
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let layout = parse(input);
-    Some(bounce_around(&layout, (0, 0), Heading::R))
+    let energized = bounce_around(&layout, (0, 0), Direction4::E, true);
+    Some(energized.len() as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let layout = parse(input);
-    let results = layout.iter().enumerate().map(|(y, components)| {
-        components
-            .iter()
-            .enumerate()
-            .filter_map(|(x, _component)| {
-                if y == 0 {
-                    Some(bounce_around(&layout, (x as isize, y as isize), Heading::D))
-                } else if y == (layout.len() - 1) {
-                    Some(bounce_around(&layout, (x as isize, y as isize), Heading::U))
-                } else if x == 0 {
-                    Some(bounce_around(&layout, (x as isize, y as isize), Heading::R))
-                } else if x % (layout[0].len() - 1) == 0 {
-                    Some(bounce_around(&layout, (x as isize, y as isize), Heading::L))
-                } else {
-                    None
-                }
-            })
-            .collect_vec()
-    });
-    results.flatten().max()
+    let (width, height) = (layout[0].len() as isize, layout.len() as isize);
+    // every beam entering from an edge, corners get two
+    let starts = (0..width)
+        .flat_map(|x| [((x, 0), Direction4::S), ((x, height - 1), Direction4::N)])
+        .chain((0..height).flat_map(|y| [((0, y), Direction4::E), ((width - 1, y), Direction4::W)]))
+        .collect_vec();
+    par::par_map(&starts, |(pos, heading)| {
+        bounce_around(&layout, *pos, *heading, false).len() as u32
+    })
+    .into_iter()
+    .max()
 }
 
 #[cfg(test)]
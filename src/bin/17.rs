@@ -1,11 +1,81 @@
+use advent_of_code::geom::{Direction4, Point2};
+use advent_of_code::grid::Grid;
+use advent_of_code::search;
+
 advent_of_code::solution!(17);
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Crucible {
+    position: Point2<isize>,
+    heading: Direction4,
+    // blocks moved in a straight line since the last turn
+    run: u8,
+}
+
+fn parse(input: &str) -> Grid<u32> {
+    Grid::parse(input, |c| {
+        c.to_digit(10).expect("heat loss should be a digit")
+    })
+}
+
+fn min_heat_loss(input: &str, min_run: u8, max_run: u8) -> Option<u32> {
+    let city = parse(input);
+    let target = Point2::new(city.width() as isize - 1, city.height() as isize - 1);
+
+    // the crucible hasn't got a heading yet, so it may leave the top-left block either way
+    let starts = [Direction4::E, Direction4::S].map(|heading| Crucible {
+        position: Point2::new(0, 0),
+        heading,
+        run: 0,
+    });
+
+    let successors = |crucible: &Crucible| {
+        let mut next = vec![];
+        for heading in [
+            crucible.heading,
+            crucible.heading.turn_left(),
+            crucible.heading.turn_right(),
+        ] {
+            let run = if heading == crucible.heading {
+                crucible.run + 1
+            } else if crucible.run >= min_run {
+                1
+            } else {
+                continue;
+            };
+            if run > max_run {
+                continue;
+            }
+            let position = crucible.position.step(heading, 1);
+            if let Some(heat_loss) = city.get(position.x, position.y) {
+                next.push((
+                    Crucible {
+                        position,
+                        heading,
+                        run,
+                    },
+                    *heat_loss,
+                ));
+            }
+        }
+        next
+    };
+
+    search::astar(
+        starts,
+        successors,
+        |crucible| crucible.position.manhattan(&target) as u32,
+        |crucible| crucible.position == target && crucible.run >= min_run,
+    )
+    .map(|(_, heat_loss)| heat_loss)
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
-    None
+    min_heat_loss(input, 0, 3)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    None
+    min_heat_loss(input, 4, 10)
 }
 
 #[cfg(test)]
@@ -15,12 +85,18 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(102));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(94));
+    }
+
+    #[test]
+    fn test_part_two_unfortunate() {
+        let input = "111111111111\n999999999991\n999999999991\n999999999991\n999999999991";
+        assert_eq!(part_two(input), Some(71));
     }
 }
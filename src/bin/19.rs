@@ -1,5 +1,4 @@
-use std::{collections::BTreeMap, str::FromStr};
-
+use advent_of_code::intern::Interner;
 use anyhow::{Error, Result};
 use itertools::Itertools;
 use nom::{
@@ -27,31 +26,30 @@ enum Rule {
     Otherwise(Target),
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum Target {
     Accepted,
     Rejected,
-    Workflow(String),
+    /// interned workflow name
+    Workflow(u32),
 }
 
-impl FromStr for Target {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "R" => Ok(Target::Rejected),
-            "A" => Ok(Target::Accepted),
-            _ => Ok(Target::Workflow(s.to_owned())),
+impl Target {
+    fn parse(name: &str, workflows: &mut Interner) -> Self {
+        match name {
+            "R" => Target::Rejected,
+            "A" => Target::Accepted,
+            _ => Target::Workflow(workflows.intern(name)),
         }
     }
 }
 
 // a<2006:qkq m>2090:A rfg
-fn parse_ruleset(input: &str) -> IResult<&str, Rule> {
+fn parse_ruleset<'a>(input: &'a str, workflows: &mut Interner) -> IResult<&'a str, Rule> {
     let (input, name) = alpha1(input)?;
     // just the "otherwise" rule
     if input.starts_with("}") {
-        let target = Target::from_str(name).unwrap();
+        let target = Target::parse(name, workflows);
         return Ok((input, Rule::Otherwise(target)));
     }
     // parse out a full conditional rule
@@ -60,8 +58,7 @@ fn parse_ruleset(input: &str) -> IResult<&str, Rule> {
         char('>').map(|_| Condition::GreaterThan),
     ))(input)?;
     let (input, amount) = map_res(digit1, |s: &str| s.parse::<u32>())(input)?;
-    let (input, target) =
-        preceded(char(':'), map(alpha1, |s| Target::from_str(s).unwrap()))(input)?;
+    let (input, target) = preceded(char(':'), map(alpha1, |s| Target::parse(s, workflows)))(input)?;
     let rule = match condition {
         Condition::GreaterThan => Rule::GreaterThan(name.to_owned(), amount, target),
         Condition::LessThan => Rule::LessThan(name.to_owned(), amount, target),
@@ -70,15 +67,16 @@ fn parse_ruleset(input: &str) -> IResult<&str, Rule> {
 }
 
 //{a<2006:qkq,m>2090:A,rfg}
-fn parse_rules(input: &str) -> IResult<&str, (String, Vec<Rule>)> {
+fn parse_rules<'a>(input: &'a str, workflows: &mut Interner) -> IResult<&'a str, (u32, Vec<Rule>)> {
     let (input, node_name) = alpha1(input)?;
+    let workflow = workflows.intern(node_name);
     Ok((
         input,
         (
-            node_name.to_owned(),
+            workflow,
             delimited(
                 char('{'),
-                separated_list1(char(','), parse_ruleset),
+                separated_list1(char(','), |input| parse_ruleset(input, workflows)),
                 char('}'),
             )(input)
             .unwrap()
@@ -87,6 +85,20 @@ fn parse_rules(input: &str) -> IResult<&str, (String, Vec<Rule>)> {
     ))
 }
 
+/// The rules of every workflow, indexed by interned workflow name.
+fn parse_workflows(input: &str) -> (Interner, Vec<Vec<Rule>>) {
+    let mut workflows = Interner::new();
+    let parsed = input
+        .lines()
+        .map(|line| parse_rules(line, &mut workflows).unwrap().1)
+        .collect_vec();
+    let mut rules: Vec<Vec<Rule>> = (0..workflows.len()).map(|_| vec![]).collect();
+    for (workflow, workflow_rules) in parsed {
+        rules[workflow as usize] = workflow_rules;
+    }
+    (workflows, rules)
+}
+
 #[derive(Debug)]
 struct Part {
     x: u32,
@@ -108,40 +120,36 @@ fn parse_part(input: &str) -> Result<Part> {
     })
 }
 
-fn determine_applicable_rule(
-    part: &Part,
-    workflow_name: &str,
-    rules: &BTreeMap<String, Vec<Rule>>,
-) -> Target {
-    let applicable_rules = rules.get(workflow_name).unwrap();
+fn determine_applicable_rule(part: &Part, workflow: u32, rules: &[Vec<Rule>]) -> Target {
+    let applicable_rules = &rules[workflow as usize];
     applicable_rules
         .iter()
         .filter_map(|r| match r {
             Rule::LessThan(field, amount, workflow) => match field.as_str() {
                 "x" => {
                     if part.x < *amount {
-                        Some(workflow.clone())
+                        Some(*workflow)
                     } else {
                         None
                     }
                 }
                 "m" => {
                     if part.m < *amount {
-                        Some(workflow.clone())
+                        Some(*workflow)
                     } else {
                         None
                     }
                 }
                 "a" => {
                     if part.a < *amount {
-                        Some(workflow.clone())
+                        Some(*workflow)
                     } else {
                         None
                     }
                 }
                 "s" => {
                     if part.s < *amount {
-                        Some(workflow.clone())
+                        Some(*workflow)
                     } else {
                         None
                     }
@@ -151,35 +159,35 @@ fn determine_applicable_rule(
             Rule::GreaterThan(field, amount, workflow) => match field.as_str() {
                 "x" => {
                     if part.x > *amount {
-                        Some(workflow.clone())
+                        Some(*workflow)
                     } else {
                         None
                     }
                 }
                 "m" => {
                     if part.m > *amount {
-                        Some(workflow.clone())
+                        Some(*workflow)
                     } else {
                         None
                     }
                 }
                 "a" => {
                     if part.a > *amount {
-                        Some(workflow.clone())
+                        Some(*workflow)
                     } else {
                         None
                     }
                 }
                 "s" => {
                     if part.s > *amount {
-                        Some(workflow.clone())
+                        Some(*workflow)
                     } else {
                         None
                     }
                 }
                 _ => None,
             },
-            Rule::Otherwise(workflow) => Some(workflow.clone()),
+            Rule::Otherwise(workflow) => Some(*workflow),
         })
         .nth(0)
         .unwrap()
@@ -188,10 +196,8 @@ fn determine_applicable_rule(
 pub fn part_one(input: &str) -> Option<u32> {
     let (rules_input, parts_input) = input.split_once("\n\n").unwrap();
 
-    let rules = rules_input
-        .lines()
-        .map(|line| parse_rules(line).unwrap().1)
-        .collect::<BTreeMap<String, Vec<Rule>>>();
+    let (workflows, rules) = parse_workflows(rules_input);
+    let start = workflows.get("in")?;
 
     let parts = parts_input
         .lines()
@@ -201,7 +207,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 
     let mut sum = 0;
     parts.iter().for_each(|part| {
-        let mut ultimate_dest = determine_applicable_rule(part, "in", &rules);
+        let mut ultimate_dest = determine_applicable_rule(part, start, &rules);
         // apply rules for 'in'
         loop {
             if ultimate_dest == Target::Rejected {
@@ -211,12 +217,12 @@ pub fn part_one(input: &str) -> Option<u32> {
                 break;
             } else {
                 // run the next rule
-                let next_dest = if let Target::Workflow(workflow_name) = ultimate_dest {
-                    workflow_name
+                let next_dest = if let Target::Workflow(workflow) = ultimate_dest {
+                    workflow
                 } else {
                     unreachable!()
                 };
-                ultimate_dest = determine_applicable_rule(part, &next_dest, &rules);
+                ultimate_dest = determine_applicable_rule(part, next_dest, &rules);
             }
         }
     });
@@ -1,170 +1,95 @@
-use std::{
-    collections::{BTreeMap, BTreeSet},
-    fmt::{Display, Write},
-};
-
+use advent_of_code::bitgrid::BitGrid;
+use advent_of_code::polynomial;
+use advent_of_code::sim::{Driver, Simulation};
+use advent_of_code::viz::{Cell, Frame, Rgb};
 use itertools::Itertools;
 
 advent_of_code::solution!(21);
 
-fn parse(input: &str) -> Garden {
-    let mut start_pos = None;
-    Garden::new(
-        input
-            .lines()
-            .enumerate()
-            .map(|(y, row)| {
-                row.chars()
-                    .enumerate()
-                    .map(|(x, square)| {
-                        if square == 'S' {
-                            start_pos = Some((x as isize, y as isize));
-                            '.'
-                        } else {
-                            square
-                        }
-                    })
-                    .collect_vec()
-            })
-            .collect_vec(),
-        start_pos.unwrap(),
-    )
+fn parse(input: &str, tiles: usize) -> Garden {
+    let plots = BitGrid::parse(input, |c| c != '#');
+    let start = BitGrid::parse(input, |c| c == 'S');
+    let (width, height) = (plots.width(), plots.height());
+    let (sx, sy) = start.iter_ones().next().unwrap();
+
+    // for part 2 we have an infinitely repeating grid, so we repeat it in every direction far enough that the
+    // frontier never reaches the edge
+    let plots = BitGrid::from_fn(width * tiles, height * tiles, |x, y| {
+        plots.get((x % width) as isize, (y % height) as isize)
+    });
+    let mut frontier = BitGrid::new(plots.width(), plots.height());
+    frontier.set(sx + tiles / 2 * width, sy + tiles / 2 * height, true);
+
+    Garden { plots, frontier }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Garden {
-    grid: Vec<Vec<char>>,
-    frontier: BTreeSet<(isize, isize)>,
-    steps_taken: usize,
-    inf_grid: bool,
+    plots: BitGrid,
+    frontier: BitGrid,
 }
 
-impl Garden {
-    fn new(grid: Vec<Vec<char>>, starting_pos: (isize, isize)) -> Self {
-        let mut frontier = BTreeSet::new();
-        frontier.insert(starting_pos);
-        Self {
-            grid,
-            frontier,
-            steps_taken: 0,
-            inf_grid: false,
-        }
+impl Simulation for Garden {
+    type Key = BitGrid;
+
+    fn step(&mut self) {
+        self.frontier = self.frontier.neighbors4() & &self.plots;
     }
 
-    fn take_a_step(&mut self) {
-        self.steps_taken += 1;
-        self.frontier = self
-            .frontier
-            .iter()
-            .map(|(x, y)| {
-                let mut new_frontier = BTreeSet::new();
-
-                // for part 2 we have an infinitely repeating grid
-                // so we'll need to translate the points and check the original grid
-                if self.inf_grid {
-                    let max_y = self.grid.len() as isize;
-                    let max_x = self.grid[0].len() as isize;
-                    // N
-                    let tx = x.rem_euclid(max_x) as usize;
-                    let ty = (y - 1).rem_euclid(max_y) as usize;
-                    if self.grid[ty][tx] == '.' {
-                        new_frontier.insert((*x, y - 1));
-                    }
-                    // S
-                    let ty = (y + 1).rem_euclid(max_y) as usize;
-                    if self.grid[ty][tx] == '.' {
-                        new_frontier.insert((*x, y + 1));
-                    }
-                    // E
-                    let tx = (x + 1).rem_euclid(max_x) as usize;
-                    let ty = y.rem_euclid(max_y) as usize;
-                    if self.grid[ty][tx] == '.' {
-                        new_frontier.insert(((x + 1), *y));
-                    }
-                    // W
-                    let tx = (x - 1).rem_euclid(max_x) as usize;
-                    if self.grid[ty][tx] == '.' {
-                        new_frontier.insert((x - 1, *y));
-                    }
-                } else {
-                    // N
-                    if *y > 0 && self.grid[*y as usize - 1][*x as usize] == '.' {
-                        new_frontier.insert((*x, y - 1));
-                    }
-                    // S
-                    if *y < (self.grid.len() - 1) as isize
-                        && self.grid[*y as usize + 1][*x as usize] == '.'
-                    {
-                        new_frontier.insert((*x, y + 1));
-                    }
-                    // E
-                    if *x < (self.grid[0].len() - 1) as isize
-                        && self.grid[*y as usize][*x as usize + 1] == '.'
-                    {
-                        new_frontier.insert(((x + 1), *y));
-                    }
-                    // W
-                    if *x > 0 && self.grid[*y as usize][*x as usize - 1] == '.' {
-                        new_frontier.insert((x - 1, *y));
-                    }
-                }
-                new_frontier
-            })
-            .flatten()
-            .collect();
+    fn state_key(&self) -> BitGrid {
+        self.frontier.clone()
     }
-}
 
-impl Display for Garden {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.grid.iter().enumerate().for_each(|(y, row)| {
-            row.iter().enumerate().for_each(|(x, square)| {
-                if self.frontier.contains(&(x as isize, y as isize)) {
-                    f.write_char('O').unwrap();
-                } else {
-                    f.write_char(*square).unwrap();
-                }
-            });
-            f.write_str("\n").unwrap();
+    fn render(&self) -> Frame {
+        let mut frame = Frame::from_fn(self.plots.width(), self.plots.height(), |x, y| {
+            let plot = self.plots.get(x as isize, y as isize);
+            Cell::new(if plot { '.' } else { '#' }).fg(Rgb::GRAY)
         });
-        Ok(())
+        for (x, y) in self.frontier.iter_ones() {
+            frame.set(x as isize, y as isize, Cell::new('O').fg(Rgb::GREEN).bold());
+        }
+        frame
     }
 }
+
+/// for part 2, enough copies of the garden in each direction that the frontier doesn't reach the edge in `steps`
+fn tiles(steps: usize, size: usize) -> usize {
+    2 * (steps / size + 1) + 1
+}
+
 pub fn part_one(input: &str) -> Option<u64> {
-    Some(walk_it(input, 64, false).unwrap().0)
+    Some(walk_it(input, 64, false))
 }
 
-pub fn walk_it(input: &str, steps: usize, inf_grid: bool) -> Option<(u64, Vec<(usize, usize)>)> {
-    let mut garden = parse(input);
-    garden.inf_grid = inf_grid;
-    let step_results = (0..steps)
-        .map(|_| {
-            garden.take_a_step();
-            (garden.steps_taken, garden.frontier.len())
-        })
-        .collect_vec();
-    // dbg!(&step_results);
-    // let mut deltas: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
-    // step_results
-    //     .iter()
-    //     .tuple_windows::<(_, _)>()
-    //     .for_each(|(a, b)| {
-    //         let d = b.1 - a.1;
-    //         deltas
-    //             .entry(d)
-    //             .and_modify(|it| it.push(b.0))
-    //             .or_insert(vec![b.0]);
-    //     });
-
-    // dbg!(&deltas);
-    Some((garden.frontier.len() as u64, step_results))
+pub fn walk_it(input: &str, steps: usize, inf_grid: bool) -> u64 {
+    let size = input.lines().count();
+    let tiles = if inf_grid { tiles(steps, size) } else { 1 };
+    let mut driver = Driver::new(parse(input, tiles));
+    if !inf_grid {
+        driver = driver.animate();
+    }
+    driver.run(steps);
+    driver.finish().frontier.count_ones() as u64
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let result = walk_it(input, 50, true).unwrap();
-    // Does NOT work for large number of steps,
-    //there's clearly a cycle/pattern we're supposed to be able to detect...
-    Some(result.0)
+    const STEPS: usize = 26501365;
+    // the start is in the middle of the garden and its row and column are clear, so after reaching the edge of
+    // the first garden the frontier crosses another garden every `size` steps and the plot count grows quadratically
+    let size = input.lines().count();
+    let offset = STEPS % size;
+    let sample_steps = (0..3).map(|n| offset + n * size).collect_vec();
+    let mut driver =
+        Driver::new(parse(input, tiles(sample_steps[2], size))).snapshot_at(sample_steps.clone());
+    driver.run(sample_steps[2]);
+    let samples = sample_steps
+        .iter()
+        .map(|steps| driver.snapshot(*steps).unwrap().frontier.count_ones() as i64)
+        .collect_vec();
+    advent_of_code::debug!("plots after {offset} + n * {size} steps: {samples:?}");
+
+    let plots = polynomial::value_at(&samples, (STEPS / size) as i64);
+    Some(plots as u64)
 }
 
 #[cfg(test)]
@@ -177,9 +102,7 @@ mod tests {
             &advent_of_code::template::read_file("examples", DAY),
             6,
             false,
-        )
-        .unwrap()
-        .0;
+        );
         assert_eq!(result, 16);
     }
 
@@ -187,11 +110,9 @@ mod tests {
     fn test_part_two() {
         let result = walk_it(
             &advent_of_code::template::read_file("examples", DAY),
-            500,
+            100,
             true,
-        )
-        .unwrap()
-        .0;
+        );
         assert_eq!(result, 6536);
     }
 }
@@ -1,53 +1,59 @@
+use advent_of_code::scan;
+use advent_of_code::voxel::{self, Aabb, Settled};
 use glam::IVec3;
 use itertools::Itertools;
-use nom::multi::separated_list1;
 
 advent_of_code::solution!(22);
 
-#[derive(Debug)]
-struct Brick {
-    front_face: IVec3,
-    back_face: IVec3,
-}
-
-fn parse(input: &str) -> Vec<Brick> {
+fn parse(input: &str) -> Vec<Aabb> {
     input
         .lines()
         .map(|line| {
-            let (f, b) = line.split_once("~").unwrap();
-            let fs: Vec<_> = f
-                .splitn(3, ',')
-                .map(|v| v.parse::<i32>().unwrap())
-                .collect();
-            let bs: Vec<_> = b
-                .splitn(3, ",")
-                .map(|v| v.parse::<i32>().unwrap())
-                .collect();
-            Brick {
-                front_face: IVec3 {
-                    x: fs[0],
-                    y: fs[1],
-                    z: fs[2],
-                },
-                back_face: IVec3 {
-                    x: bs[0],
-                    y: bs[1],
-                    z: bs[2],
-                },
-            }
+            let (x1, y1, z1, x2, y2, z2) =
+                scan!("{},{},{}~{},{},{}", line => i32, i32, i32, i32, i32, i32)
+                    .unwrap_or_else(|e| panic!("invalid brick {line:?}: {e}"));
+            Aabb::new(IVec3::new(x1, y1, z1), IVec3::new(x2, y2, z2))
         })
         .collect_vec()
 }
 
-// wth is this brick coordinate system?
-pub fn part_one(input: &str) -> Option<u32> {
-    let bricks = parse(input);
-    dbg!(bricks);
-    None
+/// The lowest z value is 1, the ground is at 0.
+fn settle(input: &str) -> Settled {
+    voxel::settle(&parse(input), 1)
+}
+
+/// Counts the bricks that fall when `removed` is disintegrated, not counting itself.
+fn chain_reaction(settled: &Settled, order: &[usize], removed: usize) -> usize {
+    let mut fallen = vec![false; settled.boxes.len()];
+    fallen[removed] = true;
+    // bricks above only rest on bricks that come earlier in the order.
+    for &id in order {
+        let supporters = &settled.supported_by[id];
+        if !supporters.is_empty() && supporters.iter().all(|&below| fallen[below]) {
+            fallen[id] = true;
+        }
+    }
+    fallen.iter().filter(|f| **f).count() - 1
+}
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let settled = settle(input);
+    advent_of_code::debug!("{} bricks settled", settled.boxes.len());
+    Some(
+        (0..settled.boxes.len())
+            .filter(|&id| settled.is_removable(id))
+            .count(),
+    )
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    None
+pub fn part_two(input: &str) -> Option<usize> {
+    let settled = settle(input);
+    let order = settled.bottom_up();
+    Some(
+        (0..settled.boxes.len())
+            .map(|id| chain_reaction(&settled, &order, id))
+            .sum(),
+    )
 }
 
 #[cfg(test)]
@@ -57,12 +63,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(5));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(7));
     }
 }
@@ -0,0 +1,172 @@
+use advent_of_code::geom::{Point2, Point3};
+use advent_of_code::linalg::{self, Line2, Rational};
+use advent_of_code::scan;
+use itertools::Itertools;
+
+advent_of_code::solution!(24);
+
+#[derive(Debug, Clone, Copy)]
+struct Hailstone {
+    position: Point3<i64>,
+    velocity: Point3<i64>,
+}
+
+impl Hailstone {
+    /// the path when looking down from above, ignoring z
+    fn path_xy(&self) -> Line2 {
+        Line2::new(
+            Point2::new(self.position.x, self.position.y),
+            Point2::new(self.velocity.x, self.velocity.y),
+        )
+    }
+}
+
+fn parse(input: &str) -> Vec<Hailstone> {
+    input
+        .lines()
+        .map(|line| {
+            let (px, py, pz, vx, vy, vz) =
+                scan!("{}, {}, {} @ {}, {}, {}", line => i64, i64, i64, i64, i64, i64)
+                    .unwrap_or_else(|e| panic!("invalid hailstone {line:?}: {e}"));
+            Hailstone {
+                position: Point3::new(px, py, pz),
+                velocity: Point3::new(vx, vy, vz),
+            }
+        })
+        .collect_vec()
+}
+
+/// Counts the pairs of hailstones whose paths cross within the test area in the future, ignoring z.
+fn crossings(input: &str, min: i64, max: i64) -> usize {
+    let (min, max) = (
+        Rational::from(i128::from(min)),
+        Rational::from(i128::from(max)),
+    );
+    let in_area = |value: Rational| min <= value && value <= max;
+    parse(input)
+        .iter()
+        .tuple_combinations()
+        .filter_map(|(a, b)| a.path_xy().intersection(&b.path_xy()))
+        .filter(|hit| hit.is_forward() && in_area(hit.point.x) && in_area(hit.point.y))
+        .count()
+}
+
+pub fn part_one(input: &str) -> Option<usize> {
+    Some(crossings(input, 200_000_000_000_000, 400_000_000_000_000))
+}
+
+fn widen(p: Point3<i64>) -> Point3<i128> {
+    Point3::new(p.x.into(), p.y.into(), p.z.into())
+}
+
+fn cross(a: Point3<i128>, b: Point3<i128>) -> Point3<i128> {
+    Point3::new(
+        a.y * b.z - a.z * b.y,
+        a.z * b.x - a.x * b.z,
+        a.x * b.y - a.y * b.x,
+    )
+}
+
+// The rock (P, V) hits every hailstone (p, v), so P - p and V - v are parallel and (P - p) x (V - v) = 0.
+// The P x V term is the same for all hailstones, so subtracting the equations of two hailstones i and j leaves
+//   P x (v_i - v_j) + (p_i - p_j) x V = p_i x v_i - p_j x v_j
+// which is linear. Its z and y components give two systems in (Px, Py, Vx, Vy) and (Px, Pz, Vx, Vz).
+// Four hailstones besides the first one make the systems square. If they happen to be singular, e.g. because two
+// hailstones move in parallel, we move on to the next four.
+pub fn part_two(input: &str) -> Option<i128> {
+    let hailstones = parse(input);
+    let first = *hailstones.first()?;
+    // measure from the first hailstone to keep the numbers small
+    let relative = hailstones
+        .iter()
+        .map(|h| (widen(h.position - first.position), widen(h.velocity)))
+        .collect_vec();
+
+    let (xy, xz) = relative[1..]
+        .windows(4)
+        .find_map(|others| solve_rock(relative[0], others))?;
+    advent_of_code::debug!("rock velocity: {}, {}, {}", xy[2], xy[3], xz[3]);
+    let origin = widen(first.position);
+    Some(xy[0] + xy[1] + xz[1] + origin.x + origin.y + origin.z)
+}
+
+/// (Px, Py, Vx, Vy) and (Px, Pz, Vx, Vz) of the rock, from the equations of `first` paired with each of `others`
+fn solve_rock(
+    (p0, v0): (Point3<i128>, Point3<i128>),
+    others: &[(Point3<i128>, Point3<i128>)],
+) -> Option<(Vec<i128>, Vec<i128>)> {
+    let (mut z_rows, mut z_rhs) = (vec![], vec![]);
+    let (mut y_rows, mut y_rhs) = (vec![], vec![]);
+    for &(p, v) in others {
+        let dp = p0 - p;
+        let dv = v0 - v;
+        let rhs = cross(p0, v0) - cross(p, v);
+        z_rows.push(vec![dv.y, -dv.x, -dp.y, dp.x]);
+        z_rhs.push(rhs.z);
+        y_rows.push(vec![-dv.z, dv.x, dp.z, -dp.x]);
+        y_rhs.push(rhs.y);
+    }
+
+    let xy = linalg::to_integers(&linalg::solve(&z_rows, &z_rhs)?)?;
+    let xz = linalg::to_integers(&linalg::solve(&y_rows, &y_rhs)?)?;
+    Some((xy, xz))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = crossings(&advent_of_code::template::read_file("examples", DAY), 7, 27);
+        assert_eq!(result, 2);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(47));
+    }
+
+    #[test]
+    fn test_part_two_full_size() {
+        // 300 hailstones with coordinates in the order of 10^14 like the puzzle input, all hit by one rock.
+        // Solving for the rock takes fractions well beyond i128.
+        let (rock, rock_velocity) = (
+            Point3::new(
+                241_384_812_563_727,
+                316_280_493_121_843,
+                188_672_015_947_305,
+            ),
+            Point3::new(301, -245, 97),
+        );
+        let mut seed = 22_u64;
+        let mut random = |range: u64| {
+            seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            ((seed >> 33) % range) as i64
+        };
+        let input = (0..300)
+            .map(|_| {
+                let t = 100_000_000_000 + random(900_000_000_000);
+                let velocity = Point3::new(
+                    random(2001) - 1000,
+                    random(2001) - 1000,
+                    random(2001) - 1000,
+                );
+                let position = rock + (rock_velocity - velocity) * t;
+                format!(
+                    "{}, {}, {} @ {}, {}, {}",
+                    position.x, position.y, position.z, velocity.x, velocity.y, velocity.z
+                )
+            })
+            .join("\n");
+        assert_eq!(part_two(&input), Some(i128::from(rock.x + rock.y + rock.z)));
+    }
+
+    #[test]
+    fn test_part_two_too_few_hailstones() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let input = input.lines().take(4).join("\n");
+        assert_eq!(part_two(&input), None);
+    }
+}
@@ -0,0 +1,46 @@
+use advent_of_code::graph::Graph;
+use advent_of_code::intern::Interner;
+
+advent_of_code::solution!(25);
+
+/// every wire connects two components, in both directions
+fn parse(input: &str) -> Graph {
+    let mut components = Interner::new();
+    let mut wires = Graph::new();
+    for line in input.lines() {
+        let (component, connected) = line.split_once(": ").unwrap();
+        let component = components.intern(component);
+        for other in connected.split_whitespace() {
+            wires.add_undirected_edge(component, components.intern(other), 1);
+        }
+    }
+    wires
+}
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let wires = parse(input);
+    let (cut, group) = wires.min_cut()?;
+    advent_of_code::debug!("cutting {cut} wires leaves a group of {}", group.len());
+    Some(group.len() * (wires.node_count() - group.len()))
+}
+
+pub fn part_two(_input: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(54));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, None);
+    }
+}
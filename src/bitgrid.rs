@@ -0,0 +1,375 @@
+//! A dense grid of booleans, packed into machine words.
+//!
+//! Every row starts at a word boundary, so shifting the whole grid by a cell moves entire words at once and
+//! simulations that update every cell per step (spreading, rolling, ...) run on 64 cells at a time. Positions
+//! follow [`crate::grid::Grid`]: `x` grows to the right and `y` downwards.
+use std::fmt::Display;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::geom::Direction4;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A rectangular grid of booleans, one bit per cell.
+///
+/// Equality and hashing compare the packed words, which makes whole grids cheap to use as keys, e.g. for
+/// [`crate::cycle::state_after`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// Creates a grid with all cells unset.
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(BITS);
+        Self {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    /// Creates a grid, setting the cells for which `f(x, y)` returns `true`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> bool) -> Self {
+        let mut grid = Self::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                if f(x, y) {
+                    grid.set(x, y, true);
+                }
+            }
+        }
+        grid
+    }
+
+    /// Parses a grid from text, setting the cells whose character matches `f`.
+    ///
+    /// # Panics
+    /// Panics if the lines are not of equal length.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> bool) -> Self {
+        let lines: Vec<&str> = input.lines().collect();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let mut grid = Self::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            assert_eq!(
+                line.chars().count(),
+                width,
+                "line {y} has a different length"
+            );
+            for (x, c) in line.chars().enumerate() {
+                if f(c) {
+                    grid.set(x, y, true);
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    fn index(&self, x: usize, y: usize) -> (usize, u64) {
+        (y * self.words_per_row + x / BITS, 1 << (x % BITS))
+    }
+
+    /// Returns whether the cell is set. Positions outside the grid are never set.
+    pub fn get(&self, x: isize, y: isize) -> bool {
+        if !self.contains(x, y) {
+            return false;
+        }
+        let (word, mask) = self.index(x as usize, y as usize);
+        self.words[word] & mask != 0
+    }
+
+    /// # Panics
+    /// Panics if the position is outside the grid.
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is outside the grid"
+        );
+        let (word, mask) = self.index(x, y);
+        if value {
+            self.words[word] |= mask;
+        } else {
+            self.words[word] &= !mask;
+        }
+    }
+
+    /// Unsets all cells.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Returns the number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns `true` if no cell is set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// Iterates over the positions of the set cells, row by row.
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, word)| {
+            let y = i / self.words_per_row;
+            let x0 = (i % self.words_per_row) * BITS;
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    (x0 + bit, y)
+                })
+            })
+        })
+    }
+
+    /// The mask of the cells within the grid for the last word of every row.
+    fn last_word_mask(&self) -> u64 {
+        match self.width % BITS {
+            0 => !0,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    /// Clears the padding bits past the end of every row.
+    fn mask_rows(&mut self) {
+        if self.words_per_row == 0 {
+            return;
+        }
+        let mask = self.last_word_mask();
+        for row in self.words.chunks_exact_mut(self.words_per_row) {
+            row[row.len() - 1] &= mask;
+        }
+    }
+
+    /// Returns the grid with every cell moved one step in `direction`. Cells moving off the grid are dropped.
+    #[must_use]
+    pub fn shifted(&self, direction: Direction4) -> Self {
+        let mut shifted = Self::new(self.width, self.height);
+        let n = self.words_per_row;
+        if n == 0 || self.height == 0 {
+            return shifted;
+        }
+
+        match direction {
+            Direction4::N => {
+                shifted.words[..n * (self.height - 1)].copy_from_slice(&self.words[n..])
+            }
+            Direction4::S => {
+                shifted.words[n..].copy_from_slice(&self.words[..n * (self.height - 1)])
+            }
+            Direction4::E => {
+                for (from, to) in self
+                    .words
+                    .chunks_exact(n)
+                    .zip(shifted.words.chunks_exact_mut(n))
+                {
+                    let mut carry = 0;
+                    for (word, target) in from.iter().zip(to.iter_mut()) {
+                        *target = (word << 1) | carry;
+                        carry = word >> (BITS - 1);
+                    }
+                }
+                shifted.mask_rows();
+            }
+            Direction4::W => {
+                for (from, to) in self
+                    .words
+                    .chunks_exact(n)
+                    .zip(shifted.words.chunks_exact_mut(n))
+                {
+                    let mut carry = 0;
+                    for (word, target) in from.iter().zip(to.iter_mut()).rev() {
+                        *target = (word >> 1) | carry;
+                        carry = word << (BITS - 1);
+                    }
+                }
+            }
+        }
+        shifted
+    }
+
+    /// Returns the cells next to a set cell in any of the four directions. The set cells themselves are only
+    /// included if they are next to another set cell.
+    #[must_use]
+    pub fn neighbors4(&self) -> Self {
+        let mut neighbors = self.shifted(Direction4::N);
+        for direction in [Direction4::E, Direction4::S, Direction4::W] {
+            neighbors |= &self.shifted(direction);
+        }
+        neighbors
+    }
+
+    /// Returns the cells that are set in `self` but not in `other`.
+    #[must_use]
+    pub fn and_not(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.zip_words(other, |a, b| a & !b);
+        result
+    }
+
+    /// Moves every set cell in `direction` until it reaches the edge, a cell of `blocked` or another set cell
+    /// that has stopped, like rocks rolling on a tilted platform.
+    pub fn roll(&mut self, direction: Direction4, blocked: &Self) {
+        loop {
+            let occupied = &*self | blocked;
+            let moved = self.shifted(direction).and_not(&occupied);
+            if moved.is_empty() {
+                break;
+            }
+            *self = self.and_not(&moved.shifted(direction.opposite())) | &moved;
+        }
+    }
+
+    fn zip_words(&mut self, other: &Self, f: impl Fn(u64, u64) -> u64) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "grids differ in size"
+        );
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a = f(*a, *b);
+        }
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $f:expr) => {
+        impl $op_assign<&BitGrid> for BitGrid {
+            /// # Panics
+            /// Panics if the grids differ in size.
+            fn $method_assign(&mut self, other: &BitGrid) {
+                self.zip_words(other, $f);
+            }
+        }
+
+        impl $op<&BitGrid> for BitGrid {
+            type Output = BitGrid;
+
+            fn $method(mut self, other: &BitGrid) -> BitGrid {
+                self.$method_assign(other);
+                self
+            }
+        }
+
+        impl $op<&BitGrid> for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method(self, other: &BitGrid) -> BitGrid {
+                self.clone().$method(other)
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| a ^ b);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut result = self.clone();
+        result.words.iter_mut().for_each(|w| *w = !*w);
+        result.mask_rows();
+        result
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let c = if self.get(x as isize, y as isize) {
+                    '#'
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::BitGrid;
+    use crate::geom::Direction4;
+
+    #[test]
+    fn sets_cells() {
+        let mut grid = BitGrid::new(70, 2);
+        grid.set(0, 0, true);
+        grid.set(69, 1, true);
+        grid.set(64, 1, true);
+        grid.set(64, 1, false);
+        assert!(grid.get(69, 1));
+        assert!(!grid.get(64, 1));
+        assert!(!grid.get(-1, 0));
+        assert_eq!(grid.count_ones(), 2);
+        assert_eq!(grid.iter_ones().collect::<Vec<_>>(), vec![(0, 0), (69, 1)]);
+        assert_eq!((!&grid).count_ones(), 138);
+    }
+
+    #[test]
+    fn shifts_across_words() {
+        let grid = BitGrid::from_fn(70, 3, |x, y| (x, y) == (63, 1) || (x, y) == (69, 1));
+
+        let east = grid.shifted(Direction4::E);
+        assert_eq!(east.iter_ones().collect::<Vec<_>>(), vec![(64, 1)]);
+        let west = grid.shifted(Direction4::W);
+        assert_eq!(west.iter_ones().collect::<Vec<_>>(), vec![(62, 1), (68, 1)]);
+        let north = grid.shifted(Direction4::N);
+        assert_eq!(
+            north.iter_ones().collect::<Vec<_>>(),
+            vec![(63, 0), (69, 0)]
+        );
+        assert!(north.shifted(Direction4::N).is_empty());
+        assert_eq!(grid.neighbors4().count_ones(), 7);
+    }
+
+    #[test]
+    fn rolls_cells() {
+        let input = "O.#.\n..O.\nO...\n.#O.";
+        let blocked = BitGrid::parse(input, |c| c == '#');
+        let mut rocks = BitGrid::parse(input, |c| c == 'O');
+
+        rocks.roll(Direction4::N, &blocked);
+        assert_eq!(rocks.to_string(), "#...\n#.#.\n..#.\n....\n");
+        rocks.roll(Direction4::E, &blocked);
+        assert_eq!(rocks.to_string(), ".#..\n..##\n...#\n....\n");
+        rocks.roll(Direction4::S, &blocked);
+        assert_eq!(rocks.to_string(), "....\n....\n.#.#\n..##\n");
+    }
+
+    #[test]
+    fn combines_grids() {
+        let a = BitGrid::parse("##.\n...", |c| c == '#');
+        let b = BitGrid::parse(".##\n..#", |c| c == '#');
+        assert_eq!((&a & &b).count_ones(), 1);
+        assert_eq!((&a | &b).count_ones(), 4);
+        assert_eq!((&a ^ &b).count_ones(), 3);
+        assert_eq!(a.and_not(&b).iter_ones().collect::<Vec<_>>(), vec![(0, 0)]);
+    }
+}
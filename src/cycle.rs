@@ -0,0 +1,196 @@
+//! Cycle detection for simulations that eventually repeat a state.
+//!
+//! A sequence `x0, f(x0), f(f(x0)), ...` over a finite state space always ends up in a loop. It is described by
+//! a [`Cycle`]: the number of steps before the loop is entered and the length of the loop.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The shape of an eventually periodic sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Index of the first state that is part of the loop, i.e. the length of the prefix.
+    pub start: usize,
+    /// Length of the loop.
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Maps step `n` onto the equivalent step that is at most `start + period - 1`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Detects the cycle with Brent's algorithm, keeping at most two states in memory.
+pub fn brent<S: Clone + Eq>(x0: S, mut f: impl FnMut(&S) -> S) -> Cycle {
+    // find the period by searching successive powers of two.
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = x0.clone();
+    let mut hare = f(&x0);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = f(&hare);
+        period += 1;
+    }
+
+    // find the start with two pointers `period` steps apart.
+    let mut tortoise = x0.clone();
+    let mut hare = x0;
+    for _ in 0..period {
+        hare = f(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// Detects the cycle with Floyd's tortoise and hare algorithm.
+pub fn floyd<S: Clone + Eq>(x0: S, mut f: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = f(&x0);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        let next = f(&hare);
+        hare = f(&next);
+    }
+
+    let mut start = 0;
+    let mut tortoise = x0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    let mut period = 1;
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        period += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// Detects the cycle by remembering every state, calling `f` only `start + period` times.
+/// Returns the states up to the first repetition along with the cycle.
+pub fn find<S: Clone + Eq + Hash>(x0: S, f: impl FnMut(&S) -> S) -> (Vec<S>, Cycle) {
+    find_by_key(x0, f, Clone::clone)
+}
+
+/// Like [`find`], but compares states by `key`, e.g. to ignore a step counter kept in the state.
+pub fn find_by_key<S, K: Eq + Hash>(
+    x0: S,
+    mut f: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> (Vec<S>, Cycle) {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut history = vec![];
+    let mut state = x0;
+
+    loop {
+        match seen.insert(key(&state), history.len()) {
+            Some(start) => {
+                let period = history.len() - start;
+                return (history, Cycle { start, period });
+            }
+            None => {
+                let next = f(&state);
+                history.push(state);
+                state = next;
+            }
+        }
+    }
+}
+
+/// Returns the state after `n` applications of `f`, skipping ahead as soon as a cycle is detected.
+pub fn state_after<S: Clone + Eq + Hash>(x0: S, n: usize, mut f: impl FnMut(&S) -> S) -> S {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut history = vec![];
+    let mut state = x0;
+
+    for step in 0..n {
+        if let Some(start) = seen.get(&state) {
+            let cycle = Cycle {
+                start: *start,
+                period: step - start,
+            };
+            return history.swap_remove(cycle.reduce(n));
+        }
+        seen.insert(state.clone(), step);
+        let next = f(&state);
+        history.push(state);
+        state = next;
+    }
+
+    state
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{brent, find, floyd, state_after, Cycle};
+
+    /// 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2 -> ...
+    fn step(x: &u32) -> u32 {
+        if *x == 5 {
+            2
+        } else {
+            x + 1
+        }
+    }
+
+    const EXPECTED: Cycle = Cycle {
+        start: 2,
+        period: 4,
+    };
+
+    #[test]
+    fn detects_cycles() {
+        assert_eq!(brent(0, step), EXPECTED);
+        assert_eq!(floyd(0, step), EXPECTED);
+        let (history, cycle) = find(0, step);
+        assert_eq!(cycle, EXPECTED);
+        assert_eq!(history, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn detects_pure_cycles() {
+        let expected = Cycle {
+            start: 0,
+            period: 3,
+        };
+        assert_eq!(brent(0, |x| (x + 1) % 3), expected);
+        assert_eq!(floyd(0, |x| (x + 1) % 3), expected);
+        assert_eq!(find(0, |x| (x + 1) % 3).1, expected);
+    }
+
+    #[test]
+    fn skips_ahead() {
+        assert_eq!(state_after(0, 0, step), 0);
+        assert_eq!(state_after(0, 1, step), 1);
+        assert_eq!(state_after(0, 6, step), 2);
+        assert_eq!(
+            state_after(0, 1_000_000_000, step),
+            2 + (1_000_000_000 - 2) % 4
+        );
+        assert_eq!(
+            state_after(0, 1_000_000_000, |x| (x + 1) % 7),
+            1_000_000_000 % 7
+        );
+        assert_eq!(EXPECTED.reduce(9), 5);
+    }
+}
@@ -0,0 +1,413 @@
+//! Points, vectors and directions on integer lattices.
+//!
+//! Points double as vectors: adding two points adds them component-wise, so a position plus a
+//! [`Direction4::delta`] is the neighboring position. In 2D, `y` grows downwards like in [`crate::grid::Grid`],
+//! i.e. [`Direction4::N`] has a delta of `(0, -1)`.
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use glam::{IVec2, IVec3};
+use num::Signed;
+
+/// A point (or vector) in 2D space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A point (or vector) in 3D space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+pub type Vector2<T> = Point2<T>;
+pub type Vector3<T> = Point3<T>;
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Signed + Copy> Point2<T> {
+    /// Distance when moving along the axes only.
+    pub fn manhattan(&self, other: &Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// Distance when diagonal moves are allowed, i.e. the number of king moves.
+    pub fn chebyshev(&self, other: &Self) -> T
+    where
+        T: PartialOrd,
+    {
+        let dx = (self.x - other.x).abs();
+        let dy = (self.y - other.y).abs();
+        if dx > dy {
+            dx
+        } else {
+            dy
+        }
+    }
+}
+
+impl<T: Signed + Copy + From<i8>> Point2<T> {
+    /// Moves the point `n` steps in `direction`.
+    #[must_use]
+    pub fn step(self, direction: Direction4, n: T) -> Self {
+        self + direction.delta::<T>() * n
+    }
+
+    /// Iterates over the orthogonal neighbors, in the order N, E, S, W.
+    pub fn neighbors4(self) -> impl Iterator<Item = Self> {
+        Direction4::ALL.into_iter().map(move |d| self + d.delta())
+    }
+
+    /// Iterates over the orthogonal and diagonal neighbors, clockwise starting at N.
+    pub fn neighbors8(self) -> impl Iterator<Item = Self> {
+        Direction8::ALL.into_iter().map(move |d| self + d.delta())
+    }
+}
+
+impl<T: Signed + Copy> Point3<T> {
+    /// Distance when moving along the axes only.
+    pub fn manhattan(&self, other: &Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    /// Distance when diagonal moves are allowed.
+    pub fn chebyshev(&self, other: &Self) -> T
+    where
+        T: PartialOrd,
+    {
+        [
+            (self.x - other.x).abs(),
+            (self.y - other.y).abs(),
+            (self.z - other.z).abs(),
+        ]
+        .into_iter()
+        .fold(T::zero(), |max, d| if d > max { d } else { max })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+macro_rules! impl_ops {
+    ($type:ident, $($field:ident),+) => {
+        impl<T: Add<Output = T>> Add for $type<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $type<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $type<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $type<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self::Output {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $type<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)+
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $type<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)+
+            }
+        }
+    };
+}
+
+impl_ops!(Point2, x, y);
+impl_ops!(Point3, x, y, z);
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl From<IVec2> for Point2<i32> {
+    fn from(v: IVec2) -> Self {
+        Self { x: v.x, y: v.y }
+    }
+}
+
+impl From<Point2<i32>> for IVec2 {
+    fn from(p: Point2<i32>) -> Self {
+        IVec2::new(p.x, p.y)
+    }
+}
+
+impl From<IVec3> for Point3<i32> {
+    fn from(v: IVec3) -> Self {
+        Self {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<Point3<i32>> for IVec3 {
+    fn from(p: Point3<i32>) -> Self {
+        IVec3::new(p.x, p.y, p.z)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// One of the four orthogonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction4 {
+    N,
+    E,
+    S,
+    W,
+}
+
+impl Direction4 {
+    /// All directions, clockwise starting at N.
+    pub const ALL: [Direction4; 4] = [Self::N, Self::E, Self::S, Self::W];
+
+    /// Turns 90 degrees counter-clockwise.
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    /// Turns 90 degrees clockwise.
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    #[must_use]
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// The offset of a single step in this direction.
+    pub fn delta<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Self::N => (0, -1),
+            Self::E => (1, 0),
+            Self::S => (0, 1),
+            Self::W => (-1, 0),
+        };
+        Point2::new(T::from(x), T::from(y))
+    }
+
+    /// Returns `true` for [`Direction4::E`] and [`Direction4::W`].
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Self::E | Self::W)
+    }
+}
+
+/// An error which can be returned when parsing a direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidDirection(pub char);
+
+impl Display for InvalidDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' is not a direction", self.0)
+    }
+}
+
+impl std::error::Error for InvalidDirection {}
+
+impl TryFrom<char> for Direction4 {
+    type Error = InvalidDirection;
+
+    /// Parses compass (`NESW`) and screen (`URDL`, `^>v<`) notations.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'N' | 'U' | '^' => Ok(Self::N),
+            'E' | 'R' | '>' => Ok(Self::E),
+            'S' | 'D' | 'v' => Ok(Self::S),
+            'W' | 'L' | '<' => Ok(Self::W),
+            _ => Err(InvalidDirection(c)),
+        }
+    }
+}
+
+/// One of the four orthogonal or four diagonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction8 {
+    /// All directions, clockwise starting at N.
+    pub const ALL: [Direction8; 8] = [
+        Self::N,
+        Self::NE,
+        Self::E,
+        Self::SE,
+        Self::S,
+        Self::SW,
+        Self::W,
+        Self::NW,
+    ];
+
+    /// Turns 45 degrees counter-clockwise.
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    /// Turns 45 degrees clockwise.
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    #[must_use]
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    /// The offset of a single step in this direction.
+    pub fn delta<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Self::N => (0, -1),
+            Self::NE => (1, -1),
+            Self::E => (1, 0),
+            Self::SE => (1, 1),
+            Self::S => (0, 1),
+            Self::SW => (-1, 1),
+            Self::W => (-1, 0),
+            Self::NW => (-1, -1),
+        };
+        Point2::new(T::from(x), T::from(y))
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(d: Direction4) -> Self {
+        match d {
+            Direction4::N => Self::N,
+            Direction4::E => Self::E,
+            Direction4::S => Self::S,
+            Direction4::W => Self::W,
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use glam::{IVec2, IVec3};
+
+    use super::{Direction4, Direction8, Point2, Point3};
+
+    #[test]
+    fn arithmetic() {
+        let a = Point2::new(1, 2);
+        let b = Point2::new(3, -4);
+        assert_eq!(a + b, Point2::new(4, -2));
+        assert_eq!(a - b, Point2::new(-2, 6));
+        assert_eq!(-a * 2, Point2::new(-2, -4));
+        assert_eq!(
+            Point3::new(1, 2, 3) + Point3::new(1, 1, 1),
+            Point3::new(2, 3, 4)
+        );
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point2::new(1i64, 6);
+        let b = Point2::new(5i64, 11);
+        assert_eq!(a.manhattan(&b), 9);
+        assert_eq!(a.chebyshev(&b), 5);
+        let c = Point3::new(0, 0, 0);
+        assert_eq!(c.manhattan(&Point3::new(1, -2, 3)), 6);
+        assert_eq!(c.chebyshev(&Point3::new(1, -2, 3)), 3);
+    }
+
+    #[test]
+    fn directions() {
+        assert_eq!(Direction4::N.turn_left(), Direction4::W);
+        assert_eq!(Direction4::W.turn_right(), Direction4::N);
+        assert_eq!(Direction4::E.opposite(), Direction4::W);
+        assert_eq!(Direction4::N.delta::<i32>(), Point2::new(0, -1));
+        assert_eq!(Direction4::try_from('R'), Ok(Direction4::E));
+        assert!(Direction4::try_from('x').is_err());
+
+        assert_eq!(Direction8::N.turn_left(), Direction8::NW);
+        assert_eq!(Direction8::NE.opposite(), Direction8::SW);
+        assert_eq!(Direction8::SE.delta::<isize>(), Point2::new(1, 1));
+        assert_eq!(Direction8::from(Direction4::S), Direction8::S);
+    }
+
+    #[test]
+    fn steps_and_neighbors() {
+        let p = Point2::new(0i32, 0);
+        assert_eq!(p.step(Direction4::S, 3), Point2::new(0, 3));
+        assert_eq!(p.neighbors4().count(), 4);
+        assert!(p.neighbors8().all(|n| n.chebyshev(&p) == 1));
+    }
+
+    #[test]
+    fn glam_interop() {
+        let p: Point2<i32> = IVec2::new(1, 2).into();
+        assert_eq!(IVec2::from(p), IVec2::new(1, 2));
+        let p: Point3<i32> = IVec3::new(1, 2, 3).into();
+        assert_eq!(IVec3::from(p), IVec3::new(1, 2, 3));
+    }
+}
@@ -0,0 +1,451 @@
+//! Graphs stored as adjacency lists over dense `u32` node ids, such as the ids handed out by
+//! [`crate::intern::Interner`].
+//!
+//! Edges are directed and weighted. Undirected graphs store every edge in both directions, see
+//! [`Graph::add_undirected_edge`]. Unlike [`crate::search`], which explores states lazily, these algorithms need
+//! the whole graph up front.
+use num::Zero;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::ops::{Add, Range, Sub};
+
+/// A directed graph with weighted edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph<W = u32> {
+    edges: Vec<Vec<(u32, W)>>,
+}
+
+impl<W> Default for Graph<W> {
+    fn default() -> Self {
+        Self { edges: vec![] }
+    }
+}
+
+impl<W> Graph<W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a graph with the nodes `0..count` and no edges.
+    pub fn with_nodes(count: usize) -> Self {
+        Self {
+            edges: (0..count).map(|_| vec![]).collect(),
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.iter().map(Vec::len).sum()
+    }
+
+    /// Iterates over all node ids.
+    pub fn nodes(&self) -> Range<u32> {
+        0..self.edges.len() as u32
+    }
+
+    /// Adds a node without edges and returns its id.
+    pub fn add_node(&mut self) -> u32 {
+        self.edges.push(vec![]);
+        self.edges.len() as u32 - 1
+    }
+
+    /// Grows the graph so that `node` exists.
+    fn ensure(&mut self, node: u32) {
+        if node as usize >= self.edges.len() {
+            self.edges.resize_with(node as usize + 1, Vec::new);
+        }
+    }
+
+    /// Adds an edge from `from` to `to`, adding the nodes if they do not exist yet.
+    pub fn add_edge(&mut self, from: u32, to: u32, weight: W) {
+        self.ensure(from.max(to));
+        self.edges[from as usize].push((to, weight));
+    }
+
+    /// Adds an edge in both directions.
+    pub fn add_undirected_edge(&mut self, a: u32, b: u32, weight: W)
+    where
+        W: Clone,
+    {
+        self.add_edge(a, b, weight.clone());
+        self.add_edge(b, a, weight);
+    }
+
+    /// Returns the outgoing edges of a node as `(target, weight)`.
+    pub fn edges(&self, node: u32) -> &[(u32, W)] {
+        &self.edges[node as usize]
+    }
+
+    /// Iterates over the targets of the outgoing edges of a node.
+    pub fn neighbors(&self, node: u32) -> impl Iterator<Item = u32> + '_ {
+        self.edges[node as usize].iter().map(|(to, _)| *to)
+    }
+
+    /// Returns the nodes in an order where every edge points forward, or [`None`] if the graph has a cycle.
+    pub fn topological_order(&self) -> Option<Vec<u32>> {
+        let mut in_degree = vec![0; self.node_count()];
+        for to in self.edges.iter().flatten().map(|(to, _)| *to) {
+            in_degree[to as usize] += 1;
+        }
+
+        let mut queue: VecDeque<u32> = self
+            .nodes()
+            .filter(|n| in_degree[*n as usize] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.node_count());
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for next in self.neighbors(node) {
+                in_degree[next as usize] -= 1;
+                if in_degree[next as usize] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        (order.len() == self.node_count()).then_some(order)
+    }
+
+    /// Splits the graph into strongly connected components, i.e. maximal sets of nodes that can all reach each
+    /// other, with Tarjan's algorithm.
+    ///
+    /// Components come in reverse topological order: no edge leads from a component to one listed before it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<u32>> {
+        const UNVISITED: usize = usize::MAX;
+        let n = self.node_count();
+        let mut index = vec![UNVISITED; n];
+        let mut lowlink = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut next_index = 0;
+
+        for root in 0..n {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            // the recursion of the textbook version, as (node, next edge to follow)
+            let mut calls = vec![(root, 0)];
+            index[root] = next_index;
+            lowlink[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, edge)) = calls.last_mut() {
+                let node = *node;
+                if let Some((next, _)) = self.edges[node].get(*edge) {
+                    *edge += 1;
+                    let next = *next as usize;
+                    if index[next] == UNVISITED {
+                        index[next] = next_index;
+                        lowlink[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        calls.push((next, 0));
+                    } else if on_stack[next] {
+                        lowlink[node] = lowlink[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some((parent, _)) = calls.last() {
+                    lowlink[*parent] = lowlink[*parent].min(lowlink[node]);
+                }
+                if lowlink[node] == index[node] {
+                    let mut component = vec![];
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(member as u32);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+}
+
+impl<W: Copy + Add<Output = W>> Graph<W> {
+    /// Compresses corridors, i.e. chains of nodes that are not kept, into single edges whose weight is the sum of
+    /// the weights along the chain.
+    ///
+    /// Returns the compressed graph, whose nodes are the kept nodes, along with the original id of every new node.
+    /// A corridor is followed as long as there is exactly one way on other than the way back, so every node with
+    /// more than two neighbors should be kept. Corridors that end without reaching a kept node are dropped, which
+    /// also drops one-way corridors walked against their direction.
+    pub fn contract(&self, mut keep: impl FnMut(u32) -> bool) -> (Graph<W>, Vec<u32>) {
+        let kept: Vec<u32> = self.nodes().filter(|node| keep(*node)).collect();
+        let mut new_ids = vec![None; self.node_count()];
+        for (new_id, node) in kept.iter().enumerate() {
+            new_ids[*node as usize] = Some(new_id as u32);
+        }
+
+        let mut graph = Graph::with_nodes(kept.len());
+        for (from, &node) in kept.iter().enumerate() {
+            for &(first, weight) in self.edges(node) {
+                let (mut prev, mut current, mut total) = (node, first, weight);
+                let end = loop {
+                    if let Some(id) = new_ids[current as usize] {
+                        break Some(id);
+                    }
+                    let mut onward = self.edges(current).iter().filter(|(to, _)| *to != prev);
+                    match (onward.next(), onward.next()) {
+                        (Some(&(next, weight)), None) => {
+                            prev = current;
+                            current = next;
+                            total = total + weight;
+                        }
+                        _ => break None,
+                    }
+                };
+                if let Some(to) = end.filter(|to| *to as usize != from) {
+                    graph.add_edge(from as u32, to, total);
+                }
+            }
+        }
+
+        (graph, kept)
+    }
+}
+
+impl<W: Copy + Ord + Zero> Graph<W> {
+    /// Finds a global minimum cut of an undirected graph with the Stoer–Wagner algorithm.
+    ///
+    /// Returns the total weight of the cut edges and the nodes on one side of the cut, or [`None`] if the graph has
+    /// fewer than two nodes. Every edge must be present in both directions with the same weight.
+    pub fn min_cut(&self) -> Option<(W, Vec<u32>)> {
+        let n = self.node_count();
+        if n < 2 {
+            return None;
+        }
+
+        let mut adjacency: Vec<HashMap<usize, W>> = vec![HashMap::new(); n];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, weight) in edges {
+                if to as usize != from {
+                    let total = adjacency[from].entry(to as usize).or_insert_with(W::zero);
+                    *total = *total + weight;
+                }
+            }
+        }
+        // the original nodes merged into every node
+        let mut groups: Vec<Vec<u32>> = self.nodes().map(|node| vec![node]).collect();
+        let mut active: Vec<usize> = (0..n).collect();
+        let mut best: Option<(W, Vec<u32>)> = None;
+
+        while active.len() > 1 {
+            // maximum adjacency order: repeatedly add the node most tightly connected to the ones added so far
+            let mut connection = vec![W::zero(); n];
+            let mut added = vec![false; n];
+            let mut order = vec![];
+            let mut heap = BinaryHeap::from([(W::zero(), active[0])]);
+            let mut last_connection = W::zero();
+            while let Some((weight, node)) = heap.pop() {
+                if added[node] || weight < connection[node] {
+                    continue;
+                }
+                added[node] = true;
+                order.push(node);
+                last_connection = weight;
+                for (&next, &weight) in &adjacency[node] {
+                    if !added[next] {
+                        connection[next] = connection[next] + weight;
+                        heap.push((connection[next], next));
+                    }
+                }
+            }
+
+            if order.len() < active.len() {
+                // the graph is disconnected, so nothing needs to be cut
+                let side = order
+                    .iter()
+                    .flat_map(|node| groups[*node].clone())
+                    .collect();
+                return Some((W::zero(), side));
+            }
+
+            let (s, t) = (order[order.len() - 2], order[order.len() - 1]);
+            if best
+                .as_ref()
+                .is_none_or(|(weight, _)| last_connection < *weight)
+            {
+                best = Some((last_connection, groups[t].clone()));
+            }
+
+            // merge t into s
+            for (next, weight) in std::mem::take(&mut adjacency[t]) {
+                adjacency[next].remove(&t);
+                if next != s {
+                    let total = adjacency[s].entry(next).or_insert_with(W::zero);
+                    *total = *total + weight;
+                    let total = adjacency[next].entry(s).or_insert_with(W::zero);
+                    *total = *total + weight;
+                }
+            }
+            let merged = std::mem::take(&mut groups[t]);
+            groups[s].extend(merged);
+            active.retain(|node| *node != t);
+        }
+
+        best
+    }
+}
+
+impl<W: Copy + Ord + Zero + Sub<Output = W>> Graph<W> {
+    /// Finds the weight of the longest simple path, visiting every node at most once, from `start` to `goal`.
+    ///
+    /// This tries all paths, so it is only feasible for small graphs, e.g. after [`Graph::contract`]. Branches are
+    /// pruned once they can not beat the best path so far, even if every node left were entered through its
+    /// heaviest edge. Returns [`None`] if the goal can not be reached.
+    pub fn longest_path(&self, start: u32, goal: u32) -> Option<W> {
+        let mut heaviest_in = vec![W::zero(); self.node_count()];
+        for &(to, weight) in self.edges.iter().flatten() {
+            heaviest_in[to as usize] = heaviest_in[to as usize].max(weight);
+        }
+        let remaining = self
+            .nodes()
+            .filter(|node| *node != start)
+            .fold(W::zero(), |sum, node| sum + heaviest_in[node as usize]);
+
+        let mut search = LongestPath {
+            graph: self,
+            goal,
+            heaviest_in,
+            visited: vec![false; self.node_count()],
+            best: None,
+        };
+        search.visited[start as usize] = true;
+        search.extend(start, W::zero(), remaining);
+        search.best
+    }
+}
+
+/// State of the depth first search of [`Graph::longest_path`].
+struct LongestPath<'a, W> {
+    graph: &'a Graph<W>,
+    goal: u32,
+    heaviest_in: Vec<W>,
+    visited: Vec<bool>,
+    best: Option<W>,
+}
+
+impl<W: Copy + Ord + Zero + Sub<Output = W>> LongestPath<'_, W> {
+    /// `remaining` is the upper bound for the weight still to be added.
+    fn extend(&mut self, node: u32, length: W, remaining: W) {
+        if node == self.goal {
+            self.best = self.best.max(Some(length));
+            return;
+        }
+        if self.best.is_some_and(|best| length + remaining <= best) {
+            return;
+        }
+
+        for &(next, weight) in self.graph.edges(node) {
+            if self.visited[next as usize] {
+                continue;
+            }
+            self.visited[next as usize] = true;
+            self.extend(
+                next,
+                length + weight,
+                remaining - self.heaviest_in[next as usize],
+            );
+            self.visited[next as usize] = false;
+        }
+    }
+}
+
+impl<W> FromIterator<(u32, u32, W)> for Graph<W> {
+    /// Builds a graph from directed `(from, to, weight)` edges.
+    fn from_iter<I: IntoIterator<Item = (u32, u32, W)>>(iter: I) -> Self {
+        let mut graph = Self::new();
+        for (from, to, weight) in iter {
+            graph.add_edge(from, to, weight);
+        }
+        graph
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Graph;
+
+    #[test]
+    fn sorts_topologically() {
+        let graph: Graph = [(0, 2, 1), (2, 1, 1), (0, 1, 1), (3, 0, 1)]
+            .into_iter()
+            .collect();
+        assert_eq!(graph.topological_order(), Some(vec![3, 0, 2, 1]));
+
+        let graph: Graph = [(0, 1, 1), (1, 0, 1)].into_iter().collect();
+        assert_eq!(graph.topological_order(), None);
+    }
+
+    #[test]
+    fn finds_components() {
+        // 0 <-> 1 -> 2 <-> 3 -> 4
+        let graph: Graph = [
+            (0, 1, 1),
+            (1, 0, 1),
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 2, 1),
+            (3, 4, 1),
+        ]
+        .into_iter()
+        .collect();
+        let mut components = graph.strongly_connected_components();
+        components.iter_mut().for_each(|c| c.sort_unstable());
+        assert_eq!(components, vec![vec![4], vec![2, 3], vec![0, 1]]);
+    }
+
+    #[test]
+    fn contracts_corridors() {
+        // a ring 0-1-2-3-4-5-0 with a branch 3-6, keeping 0 and 3
+        let mut graph = Graph::new();
+        for (a, b) in [(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0), (3, 6)] {
+            graph.add_undirected_edge(a, b, 1);
+        }
+        let (contracted, ids) = graph.contract(|node| node == 0 || node == 3);
+        assert_eq!(ids, vec![0, 3]);
+        assert_eq!(contracted.edges(0), &[(1, 3), (1, 3)]);
+        assert_eq!(contracted.edges(1), &[(0, 3), (0, 3)]);
+    }
+
+    #[test]
+    fn cuts_graphs() {
+        // two triangles joined by a single edge
+        let mut graph = Graph::new();
+        for (a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+            graph.add_undirected_edge(a, b, 2);
+        }
+        let (weight, mut side) = graph.min_cut().unwrap();
+        side.sort_unstable();
+        assert_eq!(weight, 2);
+        assert!(side == vec![0, 1, 2] || side == vec![3, 4, 5]);
+
+        let graph: Graph = [(0, 1, 1), (1, 0, 1), (2, 2, 1)].into_iter().collect();
+        assert_eq!(graph.min_cut().map(|(weight, _)| weight), Some(0));
+    }
+
+    #[test]
+    fn finds_longest_paths() {
+        let mut graph = Graph::new();
+        for (a, b, weight) in [(0, 1, 2), (1, 3, 2), (0, 2, 5), (2, 1, 1), (2, 3, 1)] {
+            graph.add_undirected_edge(a, b, weight);
+        }
+        assert_eq!(graph.longest_path(0, 3), Some(8));
+        graph.add_node();
+        assert_eq!(graph.longest_path(0, 4), None);
+    }
+}
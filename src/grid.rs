@@ -0,0 +1,383 @@
+//! A dense 2D grid, as found in most puzzle inputs.
+//!
+//! Cells are stored row by row. Positions are `(x, y)` tuples with `x` growing to the right and `y` growing
+//! downwards, which matches the order in which the input is read.
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+/// A dense, rectangular 2D grid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// Offsets of the 4 orthogonal neighbors, in the order N, E, S, W.
+pub const NEIGHBORS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of the 8 surrounding neighbors, clockwise starting at N.
+pub const NEIGHBORS_8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+impl<T> Grid<T> {
+    /// Creates a grid from cells stored row by row.
+    ///
+    /// # Panics
+    /// Panics if the number of cells does not match `width * height`.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "cells do not fit the grid");
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a grid from text, mapping every character to a cell.
+    ///
+    /// # Panics
+    /// Panics if the lines are not of equal length.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Self {
+        Self::parse_with_position(input, |_, c| f(c))
+    }
+
+    /// Parses a grid from text, mapping every character and its position to a cell.
+    ///
+    /// # Panics
+    /// Panics if the lines are not of equal length.
+    pub fn parse_with_position(input: &str, mut f: impl FnMut((usize, usize), char) -> T) -> Self {
+        let mut cells = vec![];
+        let mut width = 0;
+        let mut height = 0;
+
+        for (y, line) in input.lines().enumerate() {
+            let row_start = cells.len();
+            cells.extend(line.chars().enumerate().map(|(x, c)| f((x, y), c)));
+            let row_width = cells.len() - row_start;
+            if y == 0 {
+                width = row_width;
+            }
+            assert_eq!(row_width, width, "line {y} has a different length");
+            height += 1;
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `true` if the position lies inside the grid.
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    /// Returns the cell at the position, or [`None`] if it lies outside the grid.
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        if self.contains(x, y) {
+            Some(&self.cells[y as usize * self.width + x as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cell at the position, or [`None`] if it lies outside the grid.
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        if self.contains(x, y) {
+            Some(&mut self.cells[y as usize * self.width + x as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cell at the position of an infinite plane tiled with this grid.
+    ///
+    /// # Panics
+    /// Panics if the grid is empty.
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        assert!(!self.cells.is_empty(), "cannot wrap around an empty grid");
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        &self[(x, y)]
+    }
+
+    /// Iterates over the in-bounds orthogonal neighbors of a position.
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors(x, y, &NEIGHBORS_4)
+    }
+
+    /// Iterates over the in-bounds orthogonal and diagonal neighbors of a position.
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors(x, y, &NEIGHBORS_8)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let nx = x as isize + dx;
+            let ny = y as isize + dy;
+            self.contains(nx, ny).then_some((nx as usize, ny as usize))
+        })
+    }
+
+    /// Iterates over the rows of the grid.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Iterates over the cells of column `x`, top to bottom.
+    ///
+    /// # Panics
+    /// Panics if `x` is not less than the width.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} is out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Iterates over the columns of the grid.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Iterates over all positions and cells, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        // an empty grid has no cells, so the width only needs to be non-zero to divide by it.
+        let width = self.width.max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    /// Returns the position of the first cell matching the predicate, row by row.
+    pub fn position(&self, pred: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.cells
+            .iter()
+            .position(pred)
+            .map(|i| (i % self.width.max(1), i / self.width.max(1)))
+    }
+
+    /// Creates a new grid of the same size with every cell mapped.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> usize) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self.cells[source(x, y)].clone())
+            .collect();
+        Self::from_vec(width, height, cells)
+    }
+
+    /// Mirrors the grid along its main diagonal.
+    #[must_use]
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| x * self.width + y)
+    }
+
+    /// Rotates the grid by 90 degrees clockwise.
+    #[must_use]
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| {
+            (self.height - 1 - x) * self.width + y
+        })
+    }
+
+    /// Rotates the grid by 90 degrees counter-clockwise.
+    #[must_use]
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| {
+            x * self.width + (self.width - 1 - y)
+        })
+    }
+
+    /// Mirrors the grid left to right.
+    #[must_use]
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.width, self.height, |x, y| {
+            y * self.width + (self.width - 1 - x)
+        })
+    }
+
+    /// Mirrors the grid top to bottom.
+    #[must_use]
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.width, self.height, |x, y| {
+            (self.height - 1 - y) * self.width + x
+        })
+    }
+
+    /// Returns the cells row by row.
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self::from_vec(width, height, vec![value; width * height])
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is out of bounds"
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is out of bounds"
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Grid;
+
+    fn sample() -> Grid<char> {
+        Grid::parse("abc\ndef", |c| c)
+    }
+
+    #[test]
+    fn parses_text() {
+        let grid = sample();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get(-1, 0), None);
+        assert_eq!(grid.get(1, 1), Some(&'e'));
+        assert_eq!(grid.position(|c| *c == 'd'), Some((0, 1)));
+    }
+
+    #[test]
+    fn wraps_around() {
+        let grid = sample();
+        assert_eq!(*grid.get_wrapping(-1, -1), 'f');
+        assert_eq!(*grid.get_wrapping(3, 2), 'a');
+    }
+
+    #[test]
+    #[should_panic(expected = "empty grid")]
+    fn cannot_wrap_empty() {
+        Grid::<char>::parse("", |c| c).get_wrapping(0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn checks_columns() {
+        let _ = sample().column(3);
+    }
+
+    #[test]
+    fn handles_empty() {
+        let grid = Grid::<char>::parse("", |c| c);
+        assert_eq!(grid.iter().count(), 0);
+        assert_eq!(grid.position(|_| true), None);
+        assert_eq!(grid.columns().count(), 0);
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = sample();
+        assert_eq!(
+            grid.neighbors4(0, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbors8(1, 0).count(), 5);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = sample();
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&['a', 'b', 'c'], &['d', 'e', 'f']]
+        );
+        assert_eq!(
+            grid.columns()
+                .map(|c| c.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["ad", "be", "cf"]
+        );
+    }
+
+    #[test]
+    fn transforms() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    }
+}
@@ -0,0 +1,99 @@
+//! Interning of names, such as node or workflow names, into dense ids.
+//!
+//! Ids count up from 0 in the order names are first seen, so anything keyed by name can be stored in a plain
+//! `Vec` indexed by id instead of a map keyed by strings.
+use std::collections::HashMap;
+use std::ops::Index;
+
+/// Assigns a dense `u32` id to every distinct name, and looks names up by id.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    ids: HashMap<String, u32>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `name`, assigning the next free id if it has not been seen before.
+    pub fn intern(&mut self, name: &str) -> u32 {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = u32::try_from(self.names.len()).expect("too many names to intern");
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        id
+    }
+
+    /// Returns the id of `name`, if it has been interned.
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    /// Returns the name of an id.
+    ///
+    /// # Panics
+    /// Panics if the id was not handed out by this interner.
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    /// Returns the number of interned names, which is also the next id to be handed out.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iterates over all ids and their names, in the order of the ids.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (id as u32, name.as_str()))
+    }
+}
+
+impl Index<u32> for Interner {
+    type Output = str;
+
+    fn index(&self, id: u32) -> &str {
+        self.name(id)
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for Interner {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut interner = Self::new();
+        for name in iter {
+            interner.intern(name.as_ref());
+        }
+        interner
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Interner;
+
+    #[test]
+    fn interns_names() {
+        let mut interner: Interner = ["in", "px", "in"].into_iter().collect();
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.get("px"), Some(1));
+        assert_eq!(interner.get("qqz"), None);
+        assert_eq!(interner.intern("qqz"), 2);
+        assert_eq!(interner.intern("px"), 1);
+        assert_eq!(interner.name(2), "qqz");
+        assert_eq!(&interner[2], "qqz");
+        assert_eq!(
+            interner.iter().collect::<Vec<_>>(),
+            vec![(0, "in"), (1, "px"), (2, "qqz")]
+        );
+    }
+}
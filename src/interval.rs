@@ -0,0 +1,363 @@
+//! Arithmetic on integer intervals, for puzzles that operate on ranges too large to expand.
+//!
+//! All intervals are half-open, i.e. `[start, end)`, like [`std::ops::Range`].
+use num::PrimInt;
+use std::fmt::Display;
+use std::ops::Range;
+
+/// A half-open interval `[start, end)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// Creates the interval of `len` values starting at `start`.
+    pub fn from_len(start: T, len: T) -> Self {
+        Self::new(start, start + len)
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Returns the overlap of both intervals, or [`None`] if they are disjoint.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let result = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!result.is_empty()).then_some(result)
+    }
+
+    /// Splits the interval into the values below `at` and the values from `at` on.
+    pub fn split_at(&self, at: T) -> (Option<Self>, Option<Self>) {
+        let below = Self::new(self.start, self.end.min(at));
+        let above = Self::new(self.start.max(at), self.end);
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+
+    /// Moves the interval up by `by`.
+    #[must_use]
+    pub fn shift(&self, by: T) -> Self {
+        Self::new(self.start + by, self.end + by)
+    }
+
+    /// Moves the interval so that `from` ends up at `to`. Works for unsigned types in both directions.
+    #[must_use]
+    pub fn translate(&self, from: T, to: T) -> Self {
+        Self::new(self.start - from + to, self.end - from + to)
+    }
+}
+
+impl<T> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+/// A set of values stored as sorted, disjoint and non-adjacent intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    /// Sorts and merges arbitrary intervals.
+    fn normalize(mut intervals: Vec<Interval<T>>) -> Self {
+        intervals.retain(|i| !i.is_empty());
+        intervals.sort_unstable();
+
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        Self { intervals: merged }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = Self::normalize(intervals);
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalize(
+            self.intervals
+                .iter()
+                .chain(other.intervals.iter())
+                .copied()
+                .collect(),
+        )
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            result.extend(a.intersection(&b));
+            // advance whichever interval ends first, the other one may still overlap the next.
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals: result }
+    }
+
+    /// Returns the values of `self` that are not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = vec![];
+        let mut j = 0;
+        for interval in &self.intervals {
+            let mut start = interval.start;
+            // skip everything in `other` that ends before this interval.
+            while j < other.intervals.len() && other.intervals[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.intervals.len() && other.intervals[k].start < interval.end {
+                let cut = other.intervals[k];
+                if cut.start > start {
+                    result.push(Interval::new(start, cut.start));
+                }
+                start = start.max(cut.end);
+                k += 1;
+            }
+            if start < interval.end {
+                result.push(Interval::new(start, interval.end));
+            }
+        }
+        Self { intervals: result }
+    }
+
+    /// Splits the set into the values below `at` and the values from `at` on.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let mut below = vec![];
+        let mut above = vec![];
+        for interval in &self.intervals {
+            let (lo, hi) = interval.split_at(at);
+            below.extend(lo);
+            above.extend(hi);
+        }
+        (Self { intervals: below }, Self { intervals: above })
+    }
+
+    /// Moves every value up by `by`.
+    #[must_use]
+    pub fn shift(&self, by: T) -> Self {
+        Self {
+            intervals: self.intervals.iter().map(|i| i.shift(by)).collect(),
+        }
+    }
+
+    /// Moves every value so that `from` ends up at `to`.
+    #[must_use]
+    pub fn translate(&self, from: T, to: T) -> Self {
+        Self {
+            intervals: self
+                .intervals
+                .iter()
+                .map(|i| i.translate(from, to))
+                .collect(),
+        }
+    }
+
+    /// Total number of values in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |sum, interval| sum + interval.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        // the intervals are sorted, so the candidate is the last one starting at or before `value`.
+        let index = self.intervals.partition_point(|i| i.start <= value);
+        index > 0 && self.intervals[index - 1].contains(value)
+    }
+
+    /// Returns the smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    /// Returns the largest value in the set.
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(|i| i.end - T::one())
+    }
+
+    /// Iterates over the disjoint intervals in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        Self::normalize(vec![interval])
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        Self::normalize(iter.into_iter().collect())
+    }
+}
+
+/// A piecewise-linear map that moves source intervals onto destinations, and leaves all other values unchanged.
+///
+/// If rules overlap, the one that was added first wins.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RangeMap<T> {
+    rules: Vec<(Interval<T>, T)>,
+}
+
+impl<T: PrimInt> RangeMap<T> {
+    pub fn new() -> Self {
+        Self { rules: vec![] }
+    }
+
+    /// Adds a rule mapping the `len` values starting at `src` onto the values starting at `dst`.
+    pub fn insert(&mut self, src: T, dst: T, len: T) {
+        self.rules.push((Interval::from_len(src, len), dst));
+    }
+
+    /// Maps a single value.
+    pub fn apply(&self, value: T) -> T {
+        self.rules
+            .iter()
+            .find(|(src, _)| src.contains(value))
+            .map_or(value, |(src, dst)| value - src.start + *dst)
+    }
+
+    /// Maps all values of a set at once, splitting its intervals where rules begin and end.
+    pub fn apply_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut unmapped = set.clone();
+        let mut mapped = vec![];
+
+        for (src, dst) in &self.rules {
+            let src_set = IntervalSet::from(*src);
+            let hit = unmapped.intersection(&src_set);
+            if hit.is_empty() {
+                continue;
+            }
+            mapped.extend(hit.translate(src.start, *dst).intervals);
+            unmapped = unmapped.difference(&src_set);
+        }
+
+        mapped.extend(unmapped.intervals);
+        IntervalSet::normalize(mapped)
+    }
+}
+
+impl<T: PrimInt> FromIterator<(T, T, T)> for RangeMap<T> {
+    /// Collects `(src, dst, len)` rules.
+    fn from_iter<I: IntoIterator<Item = (T, T, T)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (src, dst, len) in iter {
+            map.insert(src, dst, len);
+        }
+        map
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Interval, IntervalSet, RangeMap};
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn merges_intervals() {
+        let s = set(&[(5, 8), (0, 2), (2, 3), (7, 10), (12, 12)]);
+        assert_eq!(s, set(&[(0, 3), (5, 10)]));
+        assert_eq!(s.len(), 8);
+        assert!(s.contains(9));
+        assert!(!s.contains(3));
+        assert_eq!(s.min(), Some(0));
+        assert_eq!(s.max(), Some(9));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        assert_eq!(a.union(&b), set(&[(0, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 28)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20), (30, 40)]));
+    }
+
+    #[test]
+    fn splits_and_shifts() {
+        let (below, above) = set(&[(0, 10), (20, 30)]).split_at(25);
+        assert_eq!(below, set(&[(0, 10), (20, 25)]));
+        assert_eq!(above, set(&[(25, 30)]));
+        assert_eq!(above.shift(-25), set(&[(0, 5)]));
+        assert_eq!(above.translate(25, 100), set(&[(100, 105)]));
+    }
+
+    #[test]
+    fn maps_ranges() {
+        // seed-to-soil map of the 2023 day 5 example.
+        let map: RangeMap<u64> = [(98, 50, 2), (50, 52, 48)].into_iter().collect();
+        assert_eq!(map.apply(79), 81);
+        assert_eq!(map.apply(99), 51);
+        assert_eq!(map.apply(10), 10);
+
+        let seeds: IntervalSet<u64> = [Interval::from_len(95, 10)].into_iter().collect();
+        let soil = map.apply_set(&seeds);
+        let expected: IntervalSet<u64> = [Interval::new(50, 52), Interval::new(97, 105)]
+            .into_iter()
+            .collect();
+        assert_eq!(soil, expected);
+        assert_eq!(soil.len(), 10);
+    }
+}
@@ -1,4 +1,24 @@
+pub mod bitgrid;
+pub mod cycle;
 mod day;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod intern;
+pub mod interval;
+pub mod linalg;
+pub mod log;
+pub mod math;
+pub mod memo;
+pub mod par;
+pub mod parse;
+pub mod polygon;
+pub mod polynomial;
+pub mod scan;
+pub mod search;
+pub mod sim;
 pub mod template;
+pub mod viz;
+pub mod voxel;
 
 pub use day::*;
@@ -0,0 +1,361 @@
+//! Exact linear algebra over rational numbers, and intersections of lines with integer coordinates.
+//!
+//! Systems of equations are solved with [`BigRational`]s, as the fractions in between grow far beyond the
+//! inputs: eliminating a 4x4 system with puzzle coordinates in the order of `10^15` overflows `i128`.
+//! Intersections of two lines only take a few products, so they use [`Rational`]s backed by `i128`.
+use crate::geom::{Point2, Point3};
+use num::rational::Ratio;
+use num::{BigInt, BigRational, Signed, ToPrimitive, Zero};
+
+/// An exact fraction.
+pub type Rational<T = i128> = Ratio<T>;
+
+/// Brings the matrix into reduced row echelon form by Gauss-Jordan elimination, and returns its rank.
+///
+/// Every pivot is 1, and is the only non-zero value in its column.
+pub fn row_reduce(matrix: &mut [Vec<BigRational>]) -> usize {
+    let columns = matrix.first().map_or(0, Vec::len);
+    let mut rank = 0;
+
+    for column in 0..columns {
+        let Some(pivot) = (rank..matrix.len()).find(|row| !matrix[*row][column].is_zero()) else {
+            continue;
+        };
+        matrix.swap(rank, pivot);
+
+        let divisor = matrix[rank][column].clone();
+        matrix[rank].iter_mut().for_each(|value| *value /= &divisor);
+        let pivot_row = matrix[rank].clone();
+        for (row, values) in matrix.iter_mut().enumerate() {
+            let factor = values[column].clone();
+            if row == rank || factor.is_zero() {
+                continue;
+            }
+            for (value, pivot) in values[column..].iter_mut().zip(&pivot_row[column..]) {
+                *value -= pivot * &factor;
+            }
+        }
+
+        rank += 1;
+        if rank == matrix.len() {
+            break;
+        }
+    }
+
+    rank
+}
+
+fn to_rationals<T: Copy + Into<BigInt>>(rows: &[Vec<T>]) -> Vec<Vec<BigRational>> {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|value| BigRational::from_integer((*value).into()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Solves the square system `matrix * x = rhs`.
+///
+/// Returns [`None`] if the system has no unique solution, i.e. the matrix is singular.
+///
+/// # Panics
+/// Panics if the matrix is not square or does not match the length of `rhs`.
+pub fn solve<T: Copy + Into<BigInt>>(matrix: &[Vec<T>], rhs: &[T]) -> Option<Vec<BigRational>> {
+    let n = rhs.len();
+    if n == 0 {
+        return Some(vec![]);
+    }
+    assert!(
+        matrix.len() == n && matrix.iter().all(|row| row.len() == n),
+        "the matrix must be square and match the right hand side"
+    );
+
+    let mut augmented = to_rationals(matrix);
+    for (row, value) in augmented.iter_mut().zip(rhs) {
+        row.push(BigRational::from_integer((*value).into()));
+    }
+    // even with rank n, the last pivot lands in the right hand side column instead of the diagonal if the
+    // matrix is singular, i.e. the system has no solution
+    if row_reduce(&mut augmented) < n || augmented[n - 1][n - 1].is_zero() {
+        return None;
+    }
+    Some(augmented.into_iter().map(|mut row| row.remove(n)).collect())
+}
+
+/// Returns the determinant of a square matrix.
+pub fn determinant<T: Copy + Into<BigInt>>(matrix: &[Vec<T>]) -> BigRational {
+    let mut matrix = to_rationals(matrix);
+    let n = matrix.len();
+    let mut determinant = BigRational::from_integer(1.into());
+
+    for column in 0..n {
+        let Some(pivot) = (column..n).find(|row| !matrix[*row][column].is_zero()) else {
+            return BigRational::zero();
+        };
+        if pivot != column {
+            matrix.swap(column, pivot);
+            determinant = -determinant;
+        }
+        let divisor = matrix[column][column].clone();
+        determinant *= &divisor;
+        let (above, below) = matrix.split_at_mut(column + 1);
+        let pivot_row = &above[column];
+        for values in below {
+            let factor = &values[column] / &divisor;
+            for (value, pivot) in values[column..].iter_mut().zip(&pivot_row[column..]) {
+                *value -= pivot * &factor;
+            }
+        }
+    }
+
+    determinant
+}
+
+/// Converts the values to integers, or returns [`None`] if any of them is a proper fraction or does not fit.
+pub fn to_integers(values: &[BigRational]) -> Option<Vec<i128>> {
+    values
+        .iter()
+        .map(|value| value.is_integer().then(|| value.to_integer().to_i128())?)
+        .collect()
+}
+
+/// Where two lines meet: `point` is at parameter `t` along the first line and `u` along the second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Intersection<P> {
+    pub point: P,
+    pub t: Rational,
+    pub u: Rational,
+}
+
+impl<P> Intersection<P> {
+    /// Returns `true` if the point lies ahead of both origins, i.e. both lines are rays rather than lines.
+    pub fn is_forward(&self) -> bool {
+        !self.t.is_negative() && !self.u.is_negative()
+    }
+}
+
+/// The line through `origin` along `direction`, i.e. all points `origin + t * direction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line2 {
+    pub origin: Point2<i64>,
+    pub direction: Point2<i64>,
+}
+
+impl Line2 {
+    pub fn new(origin: Point2<i64>, direction: Point2<i64>) -> Self {
+        Self { origin, direction }
+    }
+
+    /// Returns the point at parameter `t`.
+    pub fn at(&self, t: Rational) -> Point2<Rational> {
+        let at = |origin: i64, direction: i64| {
+            Rational::from(i128::from(origin)) + t * i128::from(direction)
+        };
+        Point2::new(
+            at(self.origin.x, self.direction.x),
+            at(self.origin.y, self.direction.y),
+        )
+    }
+
+    /// Returns where the lines cross, or [`None`] if they are parallel (or the same line).
+    pub fn intersection(&self, other: &Self) -> Option<Intersection<Point2<Rational>>> {
+        let cross = |a: [i128; 2], b: [i128; 2]| a[0] * b[1] - a[1] * b[0];
+        let d1 = widen2(self.direction);
+        let d2 = widen2(other.direction);
+        let denominator = cross(d1, d2);
+        if denominator == 0 {
+            return None;
+        }
+
+        let w = sub2(widen2(other.origin), widen2(self.origin));
+        let t = Rational::new(cross(w, d2), denominator);
+        let u = Rational::new(cross(w, d1), denominator);
+        Some(Intersection {
+            point: self.at(t),
+            t,
+            u,
+        })
+    }
+}
+
+/// The line through `origin` along `direction`, i.e. all points `origin + t * direction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line3 {
+    pub origin: Point3<i64>,
+    pub direction: Point3<i64>,
+}
+
+impl Line3 {
+    pub fn new(origin: Point3<i64>, direction: Point3<i64>) -> Self {
+        Self { origin, direction }
+    }
+
+    /// Returns the point at parameter `t`.
+    pub fn at(&self, t: Rational) -> Point3<Rational> {
+        let at = |origin: i64, direction: i64| {
+            Rational::from(i128::from(origin)) + t * i128::from(direction)
+        };
+        Point3::new(
+            at(self.origin.x, self.direction.x),
+            at(self.origin.y, self.direction.y),
+            at(self.origin.z, self.direction.z),
+        )
+    }
+
+    /// Returns where the lines cross, or [`None`] if they are parallel or skew, i.e. pass each other.
+    pub fn intersection(&self, other: &Self) -> Option<Intersection<Point3<Rational>>> {
+        let d1 = widen3(self.direction);
+        let d2 = widen3(other.direction);
+        let normal = cross3(d1, d2);
+        if normal == [0; 3] {
+            return None;
+        }
+        let w = sub3(widen3(other.origin), widen3(self.origin));
+        if dot3(w, normal) != 0 {
+            return None;
+        }
+
+        let denominator = dot3(normal, normal);
+        let t = Rational::new(dot3(cross3(w, d2), normal), denominator);
+        let u = Rational::new(dot3(cross3(w, d1), normal), denominator);
+        Some(Intersection {
+            point: self.at(t),
+            t,
+            u,
+        })
+    }
+}
+
+fn widen2(p: Point2<i64>) -> [i128; 2] {
+    [p.x.into(), p.y.into()]
+}
+
+fn widen3(p: Point3<i64>) -> [i128; 3] {
+    [p.x.into(), p.y.into(), p.z.into()]
+}
+
+fn sub2(a: [i128; 2], b: [i128; 2]) -> [i128; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn sub3(a: [i128; 3], b: [i128; 3]) -> [i128; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross3(a: [i128; 3], b: [i128; 3]) -> [i128; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn dot3(a: [i128; 3], b: [i128; 3]) -> i128 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{determinant, row_reduce, solve, to_integers, Line2, Line3, Rational};
+    use crate::geom::{Point2, Point3};
+    use num::BigRational;
+
+    fn ratio(numer: i64, denom: i64) -> BigRational {
+        BigRational::new(numer.into(), denom.into())
+    }
+
+    #[test]
+    fn solves_systems() {
+        // x + 2y = 5, 3x - y = 1
+        let x = solve(&[vec![1, 2], vec![3, -1]], &[5, 1]).unwrap();
+        assert_eq!(x, vec![ratio(1, 1), ratio(2, 1)]);
+
+        let x = solve(
+            &[vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]],
+            &[8, -11, -3],
+        )
+        .unwrap();
+        assert_eq!(to_integers(&x), Some(vec![2, 3, -1]));
+
+        let x = solve(&[vec![2, 0], vec![0, 3]], &[1, 1]).unwrap();
+        assert_eq!(x, vec![ratio(1, 2), ratio(1, 3)]);
+        assert_eq!(to_integers(&x), None);
+
+        assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[1, 2]), None);
+    }
+
+    #[test]
+    fn solves_systems_beyond_i128() {
+        let matrix: Vec<Vec<i128>> = vec![
+            vec![982_451_653_000_017, 1_000_003, -735_632_797_000_011, 7_919],
+            vec![
+                -15_485_863,
+                838_041_641_000_009,
+                104_729,
+                -961_748_941_000_003,
+            ],
+            vec![
+                613_651_369_000_021,
+                -2_750_159,
+                899_809_343_000_001,
+                15_485_867,
+            ],
+            vec![
+                32_452_843,
+                715_225_741_000_013,
+                -49_979_687,
+                573_259_433_000_007,
+            ],
+        ];
+        let x = [1_234_567, -7_654_321, 3_141_592, -2_718_281];
+        let rhs = matrix
+            .iter()
+            .map(|row| row.iter().zip(&x).map(|(a, b)| a * b).sum())
+            .collect::<Vec<i128>>();
+        let solution = solve(&matrix, &rhs).unwrap();
+        assert_eq!(to_integers(&solution), Some(x.to_vec()));
+        assert_eq!(
+            to_integers(&[ratio(i64::MAX, 1) * ratio(i64::MAX, 1) * ratio(4, 1)]),
+            None
+        );
+    }
+
+    #[test]
+    fn reduces_rows() {
+        let mut matrix: Vec<Vec<BigRational>> = [[1, 2, 3], [2, 4, 6], [1, 0, 1]]
+            .iter()
+            .map(|row| row.iter().map(|v| ratio(*v, 1)).collect())
+            .collect();
+        assert_eq!(row_reduce(&mut matrix), 2);
+        assert_eq!(matrix[0], vec![ratio(1, 1), ratio(0, 1), ratio(1, 1)]);
+        assert_eq!(matrix[1], vec![ratio(0, 1), ratio(1, 1), ratio(1, 1)]);
+
+        assert_eq!(determinant(&[vec![0, 2], vec![3, 4]]), ratio(-6, 1));
+        assert_eq!(determinant(&[vec![1, 2], vec![2, 4]]), ratio(0, 1));
+    }
+
+    #[test]
+    fn intersects_lines() {
+        let a = Line2::new(Point2::new(19, 13), Point2::new(-2, 1));
+        let b = Line2::new(Point2::new(18, 19), Point2::new(-1, -1));
+        let hit = a.intersection(&b).unwrap();
+        assert_eq!(
+            hit.point,
+            Point2::new(Rational::new(43, 3), Rational::new(46, 3))
+        );
+        assert!(hit.is_forward());
+
+        let c = Line2::new(Point2::new(20, 25), Point2::new(-2, -2));
+        assert_eq!(b.intersection(&c), None);
+
+        let a = Line3::new(Point3::new(0, 0, 0), Point3::new(1, 1, 1));
+        let b = Line3::new(Point3::new(4, 0, 2), Point3::new(-1, 1, 0));
+        let hit = a.intersection(&b).unwrap();
+        assert_eq!(hit.point, Point3::new(2.into(), 2.into(), 2.into()));
+        assert_eq!((hit.t, hit.u), (2.into(), 2.into()));
+
+        let skew = Line3::new(Point3::new(4, 0, 3), Point3::new(-1, 1, 0));
+        assert_eq!(a.intersection(&skew), None);
+    }
+}
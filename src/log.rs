@@ -0,0 +1,101 @@
+//! Leveled debug logging for solutions.
+//!
+//! Use [`debug!`](crate::debug) and [`trace!`](crate::trace) instead of `println!` / `dbg!` in solutions.
+//! Messages are written to stderr if `solve` was called with `-v` (debug) or `-vv` (trace).
+//! Logging is muted while the runner benches a solution, and compiles to nothing in release builds
+//! unless the `logging` feature is enabled.
+use std::env;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// `true` if log statements are compiled in.
+pub const ENABLED: bool = cfg!(any(debug_assertions, feature = "logging"));
+
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+static MUTED: AtomicBool = AtomicBool::new(false);
+
+/// Verbosity of a log message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Level {
+    Debug = 1,
+    Trace = 2,
+}
+
+/// Sets the verbosity: `0` disables logging, `1` enables [`Level::Debug`], `2` and up enables [`Level::Trace`].
+pub fn set_verbosity(verbosity: u8) {
+    LEVEL.store(verbosity, Ordering::Relaxed);
+}
+
+/// Reads the verbosity from the `-v` / `-vv` command-line arguments.
+pub fn init_from_args() {
+    let verbosity = env::args()
+        .filter_map(|arg| match arg.as_str() {
+            "-v" => Some(1),
+            "-vv" => Some(2),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+    set_verbosity(verbosity);
+}
+
+/// Returns `true` if messages of `level` are currently logged.
+pub fn enabled(level: Level) -> bool {
+    ENABLED && !MUTED.load(Ordering::Relaxed) && LEVEL.load(Ordering::Relaxed) >= level as u8
+}
+
+/// Mutes all logging until the returned guard is dropped.
+#[must_use]
+pub fn mute() -> MuteGuard {
+    MuteGuard(MUTED.swap(true, Ordering::Relaxed))
+}
+
+/// Restores the previous mute state when dropped.
+pub struct MuteGuard(bool);
+
+impl Drop for MuteGuard {
+    fn drop(&mut self) {
+        MUTED.store(self.0, Ordering::Relaxed);
+    }
+}
+
+/// Logs a message to stderr if `solve` was called with `-v` or `-vv`.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::ENABLED && $crate::log::enabled($crate::log::Level::Debug) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Logs a message to stderr if `solve` was called with `-vv`.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::ENABLED && $crate::log::enabled($crate::log::Level::Trace) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{enabled, mute, set_verbosity, Level, ENABLED};
+
+    #[test]
+    fn levels_and_muting() {
+        set_verbosity(1);
+        assert_eq!(enabled(Level::Debug), ENABLED);
+        assert!(!enabled(Level::Trace));
+
+        {
+            let _guard = mute();
+            assert!(!enabled(Level::Debug));
+        }
+        assert_eq!(enabled(Level::Debug), ENABLED);
+
+        set_verbosity(0);
+        assert!(!enabled(Level::Debug));
+    }
+}
@@ -1,9 +1,14 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve};
+use advent_of_code::template::commands::solve::Profile;
+use advent_of_code::template::commands::{all, download, read, scaffold, solve, watch};
 use args::{parse, AppArguments};
 
 mod args {
+    use std::path::PathBuf;
     use std::process;
 
+    use advent_of_code::par::ParOptions;
+    use advent_of_code::template::commands::solve::{Profile, SolveOptions};
+    use advent_of_code::viz::VizOptions;
     use advent_of_code::Day;
 
     pub enum AppArguments {
@@ -18,13 +23,18 @@ mod args {
         },
         Solve {
             day: Day,
-            release: bool,
-            time: bool,
+            options: SolveOptions,
             submit: Option<u8>,
+            watch: bool,
+            test: bool,
         },
         All {
             release: bool,
+            checked: bool,
             time: bool,
+            alloc: bool,
+            par: ParOptions,
+            json: Option<PathBuf>,
         },
     }
 
@@ -34,7 +44,11 @@ mod args {
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
+                checked: args.contains("--checked"),
                 time: args.contains("--time"),
+                alloc: args.contains("--alloc"),
+                par: parse_par(&mut args)?,
+                json: args.opt_value_from_str("--json")?,
             },
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
@@ -47,9 +61,21 @@ mod args {
             },
             Some("solve") => AppArguments::Solve {
                 day: args.free_from_str()?,
-                release: args.contains("--release"),
+                options: SolveOptions {
+                    profile: Profile::from_flags(
+                        args.contains("--release"),
+                        args.contains("--checked"),
+                    ),
+                    time: args.contains("--time"),
+                    alloc: args.contains("--alloc"),
+                    verbosity: parse_verbosity(&mut args),
+                    part: args.opt_value_from_str("--part")?,
+                    viz: parse_viz(&mut args)?,
+                    par: parse_par(&mut args)?,
+                },
                 submit: args.opt_value_from_str("--submit")?,
-                time: args.contains("--time"),
+                watch: args.contains("--watch"),
+                test: args.contains("--test"),
             },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
@@ -68,6 +94,33 @@ mod args {
 
         Ok(app_args)
     }
+
+    fn parse_viz(args: &mut pico_args::Arguments) -> Result<Option<VizOptions>, pico_args::Error> {
+        let enabled = args.contains("--viz");
+        let options = VizOptions {
+            fps: args.opt_value_from_str("--viz-fps")?,
+            export: args.opt_value_from_str("--viz-export")?,
+        };
+        let requested = enabled || options.fps.is_some() || options.export.is_some();
+        Ok(requested.then_some(options))
+    }
+
+    fn parse_par(args: &mut pico_args::Arguments) -> Result<ParOptions, pico_args::Error> {
+        Ok(ParOptions {
+            threads: args.opt_value_from_str("--threads")?,
+            deterministic: args.contains("--deterministic"),
+        })
+    }
+
+    fn parse_verbosity(args: &mut pico_args::Arguments) -> u8 {
+        if args.contains("-vv") {
+            2
+        } else if args.contains("-v") {
+            1
+        } else {
+            0
+        }
+    }
 }
 
 fn main() {
@@ -77,16 +130,36 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release, time } => all::handle(release, time),
+            AppArguments::All {
+                release,
+                checked,
+                time,
+                alloc,
+                par,
+                json,
+            } => all::handle(
+                Profile::from_flags(release, checked),
+                time,
+                alloc,
+                par,
+                json,
+            ),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold { day } => scaffold::handle(day),
             AppArguments::Solve {
                 day,
-                release,
-                time,
+                options,
                 submit,
-            } => solve::handle(day, release, time, submit),
+                watch,
+                test,
+            } => {
+                if watch {
+                    watch::handle(day, &options, test);
+                } else {
+                    solve::handle(day, &options, submit);
+                }
+            }
         },
     };
 }
@@ -0,0 +1,226 @@
+//! Number theory for puzzles about cycles and remainders.
+//!
+//! Modular arithmetic works on `i64`, with `i128` intermediates so that products of two values never overflow.
+use num::integer::{gcd, Integer};
+use std::fmt::Display;
+
+/// The congruence `x ≡ residue (mod modulus)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Congruence {
+    pub residue: i64,
+    pub modulus: i64,
+}
+
+impl Congruence {
+    /// Creates the congruence with the residue normalized to `0..modulus`.
+    ///
+    /// # Panics
+    /// Panics if the modulus is not positive.
+    pub fn new(residue: i64, modulus: i64) -> Self {
+        assert!(modulus > 0, "modulus must be positive");
+        Self {
+            residue: residue.rem_euclid(modulus),
+            modulus,
+        }
+    }
+
+    /// Returns the smallest solution that is at least `min`.
+    pub fn first_at_least(&self, min: i64) -> i64 {
+        min + (self.residue - min).rem_euclid(self.modulus)
+    }
+}
+
+impl Display for Congruence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "x ≡ {} (mod {})", self.residue, self.modulus)
+    }
+}
+
+/// Why a system of congruences could not be solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrtError {
+    /// Two congruences contradict each other, e.g. `x ≡ 0 (mod 2)` and `x ≡ 1 (mod 4)`.
+    NoSolution,
+    /// The combined modulus does not fit into an `i64`.
+    Overflow,
+}
+
+impl Display for CrtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CrtError::NoSolution => write!(f, "the congruences have no common solution"),
+            CrtError::Overflow => write!(f, "the combined modulus overflows an i64"),
+        }
+    }
+}
+
+impl std::error::Error for CrtError {}
+
+/// Solves a system of congruences with the Chinese Remainder Theorem.
+///
+/// The moduli do not need to be coprime, so this also lines up cycles that have an offset, like a cycle of
+/// length 6 that is entered after 3 steps: `x ≡ 3 (mod 6)`.
+/// The solution of an empty system is `x ≡ 0 (mod 1)`.
+pub fn crt(congruences: impl IntoIterator<Item = Congruence>) -> Result<Congruence, CrtError> {
+    congruences
+        .into_iter()
+        .try_fold(Congruence::new(0, 1), merge)
+}
+
+fn merge(a: Congruence, b: Congruence) -> Result<Congruence, CrtError> {
+    let (g, p, _) = extended_gcd(a.modulus, b.modulus);
+    let diff = b.residue - a.residue;
+    if diff % g != 0 {
+        return Err(CrtError::NoSolution);
+    }
+
+    let modulus = i128::from(a.modulus / g) * i128::from(b.modulus);
+    let modulus = i64::try_from(modulus).map_err(|_| CrtError::Overflow)?;
+
+    // a.residue + k * a.modulus solves both, with k ≡ diff / g * p (mod b.modulus / g).
+    let step = i128::from(b.modulus / g);
+    let k = (i128::from(diff / g) * i128::from(p)).rem_euclid(step);
+    let residue = (i128::from(a.residue) + k * i128::from(a.modulus)).rem_euclid(modulus.into());
+    Ok(Congruence::new(residue as i64, modulus))
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y = g = gcd(a, b)`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Returns the `x` in `0..m` with `a * x ≡ 1 (mod m)`, or [`None`] if `a` and `m` are not coprime.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Returns `base^exp mod m` in `0..m`.
+pub fn mod_pow(base: i64, mut exp: u64, m: i64) -> i64 {
+    let m = i128::from(m);
+    let mut base = i128::from(base).rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as i64
+}
+
+/// Folds [`gcd`] over all values. The GCD of no values is 0.
+pub fn gcd_all<T: Integer + Copy>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::zero(), gcd)
+}
+
+/// Returns the least common multiple, or [`None`] if it overflows.
+pub fn checked_lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Folds [`checked_lcm`] over all values, e.g. the lengths of cycles that start together.
+/// The LCM of no values is 1.
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1, checked_lcm)
+}
+
+/// Returns the largest integer whose square is at most `n`.
+pub fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from above, starting at a power of two that is at least the root.
+    let mut x = 1u64 << (64 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        checked_lcm, crt, extended_gcd, gcd_all, isqrt, lcm_all, mod_inverse, mod_pow, Congruence,
+        CrtError,
+    };
+
+    #[test]
+    fn solves_coprime_systems() {
+        let solution = crt([
+            Congruence::new(2, 3),
+            Congruence::new(3, 5),
+            Congruence::new(2, 7),
+        ]);
+        assert_eq!(solution, Ok(Congruence::new(23, 105)));
+        assert_eq!(crt([]), Ok(Congruence::new(0, 1)));
+    }
+
+    #[test]
+    fn solves_offset_cycles() {
+        // cycles of length 4 and 6, hit at steps 3 and 5 of the cycle.
+        let solution = crt([Congruence::new(3, 4), Congruence::new(5, 6)]).unwrap();
+        assert_eq!(solution, Congruence::new(11, 12));
+        assert_eq!(solution.first_at_least(20), 23);
+        assert_eq!(
+            crt([Congruence::new(0, 2), Congruence::new(1, 4)]),
+            Err(CrtError::NoSolution)
+        );
+        assert_eq!(
+            crt([
+                Congruence::new(0, 4_000_000_007),
+                Congruence::new(1, 4_000_000_009),
+                Congruence::new(2, 4_000_000_011)
+            ]),
+            Err(CrtError::Overflow)
+        );
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 8), None);
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(2, 100, 1), 0);
+        assert_eq!(mod_pow(i64::MAX - 1, 2, i64::MAX), 1);
+    }
+
+    #[test]
+    fn folds_lcm_and_gcd() {
+        assert_eq!(lcm_all([2, 3, 4]), Some(12));
+        assert_eq!(lcm_all([]), Some(1));
+        assert_eq!(lcm_all([u64::MAX, 2]), None);
+        assert_eq!(checked_lcm(0, 5), Some(0));
+        assert_eq!(gcd_all([12, 18, 30]), 6);
+    }
+
+    #[test]
+    fn integer_square_roots() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+    }
+}
@@ -0,0 +1,146 @@
+//! A cache for recursive solvers, keyed on the arguments of the recursion.
+//!
+//! The computation is passed as a closure that receives the cache again, so it can recurse through it,
+//! see [`Memo::get`].
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+
+/// Cached results of a computation, along with how often the cache was hit.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: MemoStats,
+}
+
+/// How often a [`Memo`] could answer from its cache, counted since it was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl MemoStats {
+    /// Returns the share of lookups answered from the cache, between 0 and 1.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            stats: MemoStats::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached value for `key`, or computes it with `compute` and caches it.
+    ///
+    /// `compute` gets the cache passed in, so it can make recursive calls.
+    pub fn get(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// Forgets all cached values, e.g. before moving on to the next line of input. The statistics are kept.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Returns the number of cached values.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /// Logs the hit and miss counts with [`debug!`](crate::debug), i.e. if `solve` was called with `-v`.
+    pub fn log_stats(&self, name: &str) {
+        crate::debug!("{name}: {}", self.stats);
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Memo, MemoStats};
+
+    fn paths(memo: &mut Memo<(u32, u32), u64>, x: u32, y: u32) -> u64 {
+        memo.get((x, y), |memo| {
+            if x == 0 || y == 0 {
+                1
+            } else {
+                paths(memo, x - 1, y) + paths(memo, x, y - 1)
+            }
+        })
+    }
+
+    fn fib(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+        memo.get(n, |memo| {
+            if n < 2 {
+                n
+            } else {
+                fib(memo, n - 1) + fib(memo, n - 2)
+            }
+        })
+    }
+
+    #[test]
+    fn caches_recursion() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(&mut memo, 90), 2_880_067_194_370_816_120);
+        assert_eq!(memo.stats().misses, 91);
+
+        let mut memo = Memo::new();
+        assert_eq!(paths(&mut memo, 16, 16), 601_080_390);
+        assert_eq!(memo.len(), 17 * 17 - 1);
+        let stats = memo.stats();
+        assert_eq!(stats.misses, memo.len() as u64);
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(paths(&mut memo, 1, 1), 2);
+        assert_eq!(memo.stats().misses, stats.misses + 3);
+    }
+
+    #[test]
+    fn reports_stats() {
+        let stats = MemoStats { hits: 3, misses: 1 };
+        assert_eq!(stats.to_string(), "3 hits, 1 misses (75.0% hit rate)");
+        assert_eq!(MemoStats::default().hit_rate(), 0.0);
+    }
+}
@@ -0,0 +1,304 @@
+//! Helpers to spread independent work across threads, such as evaluating every record or every start position.
+//!
+//! The number of threads defaults to the available parallelism and can be set with `--threads <n>`. With
+//! `--deterministic`, everything runs on the calling thread, so benchmarks do not depend on the machine's load.
+//! Results are always returned in input order, so the outcome does not depend on the number of threads.
+//!
+//! The work runs on a pool of worker threads that is started by the first call and lives until the process exits,
+//! so calling [`par_map`] in a hot loop, e.g. once per [`Simulation::step`](crate::sim::Simulation::step), only
+//! costs a few channel messages per call. The calling thread works along, so nested calls cannot run out of threads.
+use std::any::Any;
+use std::env;
+use std::iter::Sum;
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread;
+
+/// `0` means the available parallelism.
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+static POOL: OnceLock<Pool> = OnceLock::new();
+
+/// Settings read from the `--threads` and `--deterministic` arguments of a solution binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParOptions {
+    pub threads: Option<usize>,
+    pub deterministic: bool,
+}
+
+impl ParOptions {
+    /// Builds the arguments that are forwarded to a solution binary.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(threads) = self.threads {
+            args.push("--threads".to_string());
+            args.push(threads.to_string());
+        }
+        if self.deterministic {
+            args.push("--deterministic".to_string());
+        }
+        args
+    }
+
+    fn from_args(args: &[String]) -> Self {
+        Self {
+            threads: args
+                .iter()
+                .position(|arg| arg == "--threads")
+                .and_then(|i| args.get(i + 1))
+                .and_then(|threads| threads.parse().ok()),
+            deterministic: args.iter().any(|arg| arg == "--deterministic"),
+        }
+    }
+
+    /// Returns the number of threads to use, where `0` stands for the available parallelism.
+    fn threads(&self) -> usize {
+        if self.deterministic {
+            1
+        } else {
+            self.threads.unwrap_or(0)
+        }
+    }
+}
+
+/// Reads the `--threads` and `--deterministic` arguments. Called by the `solution!` macro.
+pub fn init_from_args() {
+    let args: Vec<String> = env::args().collect();
+    set_threads(ParOptions::from_args(&args).threads());
+}
+
+/// Sets the number of threads, `0` restores the default of using the available parallelism.
+///
+/// The pool is sized by the number of threads at the first call to [`par_map`], so raising it afterwards has no
+/// effect, while lowering it still limits how many of the workers are used.
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+/// Returns the number of threads work is spread across.
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        threads => threads,
+    }
+}
+
+/// Applies `f` to every item, on as many threads as configured, and returns the results in the order of the items.
+///
+/// The threads take items one by one until all are done, so uneven work is balanced out.
+pub fn par_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = threads().min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+    let work = || {
+        let mut done = vec![];
+        loop {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            let Some(item) = items.get(i) else {
+                break;
+            };
+            done.push((i, f(item)));
+        }
+        results.lock().unwrap().extend(done);
+    };
+    Pool::get().run(threads - 1, &work);
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_unstable_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Sums `f` over all items, evaluating them like [`par_map`].
+pub fn par_sum<T, S, F>(items: &[T], f: F) -> S
+where
+    T: Sync,
+    S: Send + Sum,
+    F: Fn(&T) -> S + Sync,
+{
+    par_map(items, f).into_iter().sum()
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Worker threads waiting for jobs on a shared channel.
+struct Pool {
+    jobs: Mutex<Sender<Job>>,
+    workers: usize,
+}
+
+impl Pool {
+    fn get() -> &'static Pool {
+        POOL.get_or_init(|| Pool::start(threads().saturating_sub(1)))
+    }
+
+    fn start(workers: usize) -> Self {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        for i in 0..workers {
+            let queue = Arc::clone(&queue);
+            thread::Builder::new()
+                .name(format!("par-{i}"))
+                .spawn(move || loop {
+                    let job = queue.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                })
+                .expect("failed to start worker thread");
+        }
+        Self {
+            jobs: Mutex::new(jobs),
+            workers,
+        }
+    }
+
+    /// Runs `work` on the calling thread and on up to `helpers` workers, and returns once all of them are done.
+    /// A panic in any of them is passed on to the caller.
+    fn run(&self, helpers: usize, work: &(dyn Fn() + Sync)) {
+        // SAFETY: the batch only hands out `work` until it is closed below, and this does not return (or unwind)
+        // before every worker that picked it up has finished with it.
+        let work: &'static (dyn Fn() + Sync) = unsafe { std::mem::transmute(work) };
+        let batch = Arc::new(Batch {
+            work,
+            state: Mutex::new(BatchState::default()),
+            done: Condvar::new(),
+        });
+
+        let jobs = self.jobs.lock().unwrap();
+        for _ in 0..helpers.min(self.workers) {
+            let batch = Arc::clone(&batch);
+            jobs.send(Box::new(move || batch.help()))
+                .expect("worker threads are gone");
+        }
+        drop(jobs);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(work));
+        let mut state = batch.state.lock().unwrap();
+        // workers that get to the batch after this point have nothing left to do, so don't wait for them.
+        state.closed = true;
+        while state.active > 0 {
+            state = batch.done.wait(state).unwrap();
+        }
+        let helper_panic = state.panic.take();
+        drop(state);
+
+        if let Err(payload) = result {
+            panic::resume_unwind(payload);
+        }
+        if let Some(payload) = helper_panic {
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+/// One call of [`Pool::run`], shared with the workers that help out.
+struct Batch {
+    work: &'static (dyn Fn() + Sync),
+    state: Mutex<BatchState>,
+    done: Condvar,
+}
+
+#[derive(Default)]
+struct BatchState {
+    active: usize,
+    closed: bool,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl Batch {
+    fn help(&self) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return;
+            }
+            state.active += 1;
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(self.work));
+        let mut state = self.state.lock().unwrap();
+        state.active -= 1;
+        if let Err(payload) = result {
+            state.panic.get_or_insert(payload);
+        }
+        self.done.notify_all();
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{par_map, par_sum, set_threads, ParOptions, POOL};
+    use std::collections::HashSet;
+    use std::panic;
+    use std::thread;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn keeps_order() {
+        let items: Vec<u64> = (0..1000).collect();
+        let doubled = par_map(&items, |x| x * 2);
+        assert_eq!(doubled, items.iter().map(|x| x * 2).collect::<Vec<_>>());
+        assert_eq!(par_sum::<_, u64, _>(&items, |x| *x), 499_500);
+        assert!(par_map(&[] as &[u8], |x| *x).is_empty());
+    }
+
+    #[test]
+    fn reuses_workers() {
+        set_threads(4);
+        let items: Vec<u64> = (0..64).collect();
+        let mut names = HashSet::new();
+        for _ in 0..50 {
+            let ran_on = par_map(&items, |_| {
+                thread::current().name().map(ToString::to_string)
+            });
+            names.extend(ran_on.into_iter().flatten());
+        }
+        let workers = POOL.get().unwrap().workers;
+        assert!(names.iter().filter(|name| name.starts_with("par-")).count() <= workers);
+
+        // workers that are busy with the outer call must not keep the inner calls from finishing.
+        let nested = par_map(&items, |x| par_sum::<_, u64, _>(&items, |y| x * y));
+        assert_eq!(nested[3], 3 * 2016);
+    }
+
+    #[test]
+    fn passes_on_panics() {
+        set_threads(4);
+        let items: Vec<u64> = (0..64).collect();
+        let result = panic::catch_unwind(|| {
+            par_map(&items, |x| {
+                assert_ne!(*x, 42, "bad item");
+                x * 2
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(par_map(&items, |x| x * 2)[42], 84);
+    }
+
+    #[test]
+    fn reads_arguments() {
+        let options = ParOptions::from_args(&args(&["01", "--threads", "4"]));
+        assert_eq!(options.threads(), 4);
+        assert_eq!(options.to_args(), args(&["--threads", "4"]));
+
+        let options = ParOptions::from_args(&args(&["01", "--threads", "4", "--deterministic"]));
+        assert_eq!(options.threads(), 1);
+        assert_eq!(ParOptions::from_args(&args(&["01"])).threads(), 0);
+    }
+}
@@ -0,0 +1,217 @@
+//! Reusable [`nom`] parsers for common puzzle input shapes.
+//!
+//! Combine them with the ones from `nom` and run the result with [`parse_all`], which turns a failure into an
+//! error pointing at the offending input:
+//!
+//! ```text
+//! error: unexpected input at line 2, column 4
+//!   |
+//! 2 | 12 ab
+//!   |    ^
+//! ```
+use crate::grid::Grid;
+use nom::{
+    bytes::complete::is_not,
+    character::complete::{char, digit1, line_ending, multispace0, one_of, space0, space1},
+    combinator::{all_consuming, map_res, opt, recognize},
+    error::{Error, ErrorKind},
+    multi::separated_list1,
+    sequence::{pair, separated_pair, terminated, tuple},
+    IResult, Parser,
+};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Parses an integer with an optional `+` or `-` sign.
+pub fn signed<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+/// Parses an integer without a sign.
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// Parses numbers separated by spaces or tabs, e.g. `0 3 -6 9`. Leading spaces are skipped.
+pub fn numbers<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+    let (input, _) = space0(input)?;
+    separated_list1(space1, signed)(input)
+}
+
+/// Parses `key: value`, with optional spaces around the colon.
+pub fn key_value<'a, K, V>(
+    key: impl Parser<&'a str, K, Error<&'a str>>,
+    value: impl Parser<&'a str, V, Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (K, V)> {
+    separated_pair(key, tuple((space0, char(':'), space0)), value)
+}
+
+/// Parses one item per line.
+pub fn lines<'a, O>(
+    item: impl Parser<&'a str, O, Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(line_ending, item)
+}
+
+/// Parses blocks that are separated by blank lines.
+pub fn blocks<'a, O>(
+    block: impl Parser<&'a str, O, Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(pair(line_ending, line_ending), block)
+}
+
+/// Parses a rectangular grid, mapping every character to a cell.
+/// Fails at the first character that `cell` does not accept, or at the first line of a different length.
+pub fn grid<'a, T>(
+    cell: impl Fn(char) -> Option<T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Grid<T>> {
+    move |input: &'a str| {
+        let (rest, rows) = separated_list1(line_ending, is_not("\r\n"))(input)?;
+
+        let width = rows[0].chars().count();
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for row in &rows {
+            if row.chars().count() != width {
+                return Err(nom::Err::Failure(Error::new(row, ErrorKind::Verify)));
+            }
+            for (i, c) in row.char_indices() {
+                match cell(c) {
+                    Some(value) => cells.push(value),
+                    None => return Err(nom::Err::Failure(Error::new(&row[i..], ErrorKind::Char))),
+                }
+            }
+        }
+
+        Ok((rest, Grid::from_vec(width, rows.len(), cells)))
+    }
+}
+
+/// A parse failure, with the position of the input that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, in characters.
+    pub column: usize,
+    pub message: String,
+    /// The offending line.
+    pub source_line: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(
+            f,
+            "error: {} at line {}, column {}",
+            self.message, self.line, self.column
+        )?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{gutter} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// Locates `remaining`, which must be a slice of `input`.
+    fn new(input: &str, remaining: &str, message: String) -> Self {
+        let offset = remaining.as_ptr() as usize - input.as_ptr() as usize;
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message,
+            source_line: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        }
+    }
+}
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Digit => "expected a digit".to_string(),
+        ErrorKind::Char | ErrorKind::OneOf => "unexpected character".to_string(),
+        ErrorKind::Tag => "unexpected input".to_string(),
+        ErrorKind::Space | ErrorKind::MultiSpace => "expected whitespace".to_string(),
+        ErrorKind::CrLf => "expected a line break".to_string(),
+        ErrorKind::Eof => "unexpected input".to_string(),
+        ErrorKind::MapRes => "invalid value".to_string(),
+        ErrorKind::Verify => "line length differs from the first line".to_string(),
+        kind => format!("failed to parse ({})", kind.description()),
+    }
+}
+
+/// Runs `parser` on the whole input, allowing trailing whitespace such as the final newline.
+pub fn parse_all<'a, O>(
+    input: &'a str,
+    parser: impl Parser<&'a str, O, Error<&'a str>>,
+) -> Result<O, ParseError> {
+    match all_consuming(terminated(parser, multispace0))(input) {
+        Ok((_, output)) => Ok(output),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+            Err(ParseError::new(input, e.input, describe(e.code)))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::new(
+            input,
+            &input[input.len()..],
+            "unexpected end of input".to_string(),
+        )),
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{blocks, grid, key_value, lines, numbers, parse_all, signed, unsigned};
+    use nom::character::complete::alpha1;
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(signed::<i32>("-12 rest"), Ok((" rest", -12)));
+        assert_eq!(signed::<i32>("+7"), Ok(("", 7)));
+        assert!(unsigned::<u32>("-1").is_err());
+        assert_eq!(numbers::<i64>("  1 -2\t3\n"), Ok(("\n", vec![1, -2, 3])));
+
+        let parsed: Vec<Vec<i32>> = parse_all("1 2 3\n4 -5 6\n", lines(numbers)).unwrap();
+        assert_eq!(parsed, vec![vec![1, 2, 3], vec![4, -5, 6]]);
+    }
+
+    #[test]
+    fn parses_structures() {
+        let input = "Time: 7 15\nDistance: 9 40\n\nTime: 1\nDistance: 2";
+        let parsed = parse_all(input, blocks(lines(key_value(alpha1, numbers::<u32>)))).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0][1], ("Distance", vec![9, 40]));
+        assert_eq!(parsed[1][0], ("Time", vec![1]));
+    }
+
+    #[test]
+    fn parses_grids() {
+        let parsed = parse_all("#.\n.#\n", grid(|c| Some(c == '#'))).unwrap();
+        assert_eq!(parsed[(1, 1)], true);
+
+        let error = parse_all("#.\n.?\n", grid(|c| "#.".contains(c).then_some(c))).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        let error = parse_all("#.\n.\n", grid(Some)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+
+    #[test]
+    fn reports_errors() {
+        let error = parse_all("1 2 3\n4 x 6\n", lines(numbers::<i32>)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = parse_all("1 2\n12 ab\n", lines(numbers::<u32>)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "error: unexpected input at line 2, column 4\n  |\n2 | 12 ab\n  |    ^"
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Area and lattice-point counts of simple polygons with integer vertices.
+//!
+//! Uses the shoelace formula for the area and Pick's theorem (`A = i + b / 2 - 1`) to count the lattice points
+//! inside, so that shapes spanning billions of tiles can be measured without filling them.
+//! Results are `i128` since twice the area of a polygon with `i64` coordinates can overflow an `i64`.
+use crate::geom::{Direction4, Point2};
+use num::Integer;
+
+/// A simple polygon given by its vertices in order, either clockwise or counter-clockwise.
+/// The last vertex connects back to the first one.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Polygon {
+    vertices: Vec<Point2<i64>>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point2<i64>>) -> Self {
+        Self { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point2<i64>] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point2<i64>, Point2<i64>)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    }
+
+    /// Returns twice the area, which is always an integer.
+    pub fn double_area(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| i128::from(a.x) * i128::from(b.y) - i128::from(b.x) * i128::from(a.y))
+            .sum::<i128>()
+            .abs()
+    }
+
+    /// Returns the area, rounded down to an integer.
+    pub fn area(&self) -> i128 {
+        self.double_area() / 2
+    }
+
+    /// Number of lattice points on the boundary. For axis-aligned edges, this is the perimeter.
+    pub fn boundary_points(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| {
+                let dx = i128::from(b.x - a.x).abs();
+                let dy = i128::from(b.y - a.y).abs();
+                dx.gcd(&dy)
+            })
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside the polygon, by Pick's theorem.
+    pub fn interior_points(&self) -> i128 {
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+
+    /// Number of lattice points inside or on the boundary, e.g. the tiles covered by a trench and its interior.
+    pub fn lattice_points(&self) -> i128 {
+        self.interior_points() + self.boundary_points()
+    }
+}
+
+impl FromIterator<Point2<i64>> for Polygon {
+    fn from_iter<I: IntoIterator<Item = Point2<i64>>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// Builds a [`Polygon`] by walking from a start point, as in "go 6 steps right, then 5 down".
+#[derive(Debug, Clone)]
+pub struct PolygonBuilder {
+    position: Point2<i64>,
+    vertices: Vec<Point2<i64>>,
+}
+
+impl Default for PolygonBuilder {
+    fn default() -> Self {
+        Self::starting_at(Point2::new(0, 0))
+    }
+}
+
+impl PolygonBuilder {
+    /// Starts walking at the origin.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn starting_at(position: Point2<i64>) -> Self {
+        Self {
+            position,
+            vertices: vec![position],
+        }
+    }
+
+    /// Walks `distance` steps towards `direction` and adds a vertex at the new position.
+    pub fn step(&mut self, direction: Direction4, distance: i64) -> &mut Self {
+        self.position = self.position.step(direction, distance);
+        self.vertices.push(self.position);
+        self
+    }
+
+    /// Finishes the polygon. Returning to the start point at the end of the walk is optional.
+    pub fn build(&self) -> Polygon {
+        let mut vertices = self.vertices.clone();
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        Polygon::new(vertices)
+    }
+}
+
+impl Extend<(Direction4, i64)> for PolygonBuilder {
+    fn extend<I: IntoIterator<Item = (Direction4, i64)>>(&mut self, iter: I) {
+        for (direction, distance) in iter {
+            self.step(direction, distance);
+        }
+    }
+}
+
+impl FromIterator<(Direction4, i64)> for Polygon {
+    fn from_iter<I: IntoIterator<Item = (Direction4, i64)>>(iter: I) -> Self {
+        let mut builder = PolygonBuilder::new();
+        builder.extend(iter);
+        builder.build()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Polygon, PolygonBuilder};
+    use crate::geom::{Direction4, Point2};
+
+    #[test]
+    fn measures_squares() {
+        let square: Polygon = [(0, 0), (4, 0), (4, 4), (0, 4)]
+            .into_iter()
+            .map(Point2::from)
+            .collect();
+        assert_eq!(square.area(), 16);
+        assert_eq!(square.boundary_points(), 16);
+        assert_eq!(square.interior_points(), 9);
+        assert_eq!(square.lattice_points(), 25);
+    }
+
+    #[test]
+    fn measures_triangles() {
+        let triangle: Polygon = [(0, 0), (4, 0), (0, 3)]
+            .into_iter()
+            .map(Point2::from)
+            .collect();
+        assert_eq!(triangle.double_area(), 12);
+        assert_eq!(triangle.boundary_points(), 8);
+        assert_eq!(triangle.interior_points(), 3);
+    }
+
+    #[test]
+    fn builds_from_directions() {
+        use Direction4::*;
+        let polygon = PolygonBuilder::new()
+            .step(E, 2)
+            .step(S, 2)
+            .step(W, 2)
+            .step(N, 2)
+            .build();
+        assert_eq!(polygon.vertices().len(), 4);
+        assert_eq!(polygon.lattice_points(), 9);
+
+        let huge: Polygon = [(E, 1_000_000_000), (S, 1_000_000_000), (W, 1_000_000_000)]
+            .into_iter()
+            .collect();
+        assert_eq!(huge.area(), 1_000_000_000_000_000_000);
+    }
+}
@@ -0,0 +1,221 @@
+//! Sequences that follow a polynomial, and polynomials fitted through sample points.
+//!
+//! Sequences are extrapolated with difference tables, which is exact for integers. Fitted polynomials use rational
+//! coefficients, so no precision is lost when the coefficients are fractions.
+use num::rational::Ratio;
+use num::{One, Zero};
+use std::fmt::Display;
+
+/// Returns the differences between consecutive values.
+pub fn differences(sequence: &[i64]) -> Vec<i64> {
+    sequence.windows(2).map(|w| w[1] - w[0]).collect()
+}
+
+/// Returns the rows of the difference table, starting with the sequence itself and ending with the first row that
+/// is constant (or has a single value left).
+fn difference_table(sequence: &[i64]) -> Vec<Vec<i64>> {
+    let mut table = vec![sequence.to_vec()];
+    while let Some(row) = table.last() {
+        if row.len() <= 1 || row.iter().all(|v| *v == row[0]) {
+            break;
+        }
+        let next = differences(row);
+        table.push(next);
+    }
+    table
+}
+
+/// Returns the degree of the polynomial generating the sequence, or [`None`] if the sequence is too short to tell.
+///
+/// The degree is only known once a row of the difference table has at least two equal values, e.g. a quadratic
+/// sequence needs at least four values.
+pub fn degree(sequence: &[i64]) -> Option<usize> {
+    let table = difference_table(sequence);
+    let last = table.last()?;
+    (last.len() >= 2).then_some(table.len() - 1)
+}
+
+/// Returns the value following the sequence.
+pub fn next_value(sequence: &[i64]) -> i64 {
+    difference_table(sequence)
+        .iter()
+        .filter_map(|row| row.last())
+        .sum()
+}
+
+/// Returns the value preceding the sequence.
+pub fn prev_value(sequence: &[i64]) -> i64 {
+    difference_table(sequence)
+        .iter()
+        .rev()
+        .filter_map(|row| row.first())
+        .fold(0, |below, first| first - below)
+}
+
+/// Returns the value at `index` of the sequence, where the given values are at indices `0..sequence.len()`.
+/// The index may be negative or far beyond the sequence.
+///
+/// Uses Newton's forward difference formula, so this does not step through all values in between.
+pub fn value_at(sequence: &[i64], index: i64) -> i128 {
+    let index = i128::from(index);
+    let mut binomial: i128 = 1;
+    let mut value = 0;
+    for (k, row) in difference_table(sequence).iter().enumerate() {
+        if k > 0 {
+            // C(index, k) from C(index, k - 1), the division is always exact.
+            binomial = binomial * (index - k as i128 + 1) / k as i128;
+        }
+        value += binomial * i128::from(row[0]);
+    }
+    value
+}
+
+/// A polynomial with rational coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    /// Coefficients, starting with the constant term.
+    coefficients: Vec<Ratio<i128>>,
+}
+
+impl Polynomial {
+    /// Creates a polynomial from its coefficients, starting with the constant term.
+    pub fn new(coefficients: Vec<Ratio<i128>>) -> Self {
+        let mut polynomial = Self { coefficients };
+        polynomial.trim();
+        polynomial
+    }
+
+    /// Fits the polynomial of the lowest degree through the points, by Lagrange interpolation.
+    ///
+    /// # Panics
+    /// Panics if two points share the same `x`.
+    pub fn fit(points: &[(i64, i64)]) -> Self {
+        let mut coefficients = vec![Ratio::zero(); points.len()];
+
+        for (i, &(xi, yi)) in points.iter().enumerate() {
+            // the basis polynomial is 1 at xi and 0 at all other points.
+            let mut basis = vec![Ratio::from_integer(i128::from(yi))];
+            for (j, &(xj, _)) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                assert_ne!(xi, xj, "points must have distinct x values");
+                let denominator = Ratio::from_integer(i128::from(xi - xj));
+                // multiply by (x - xj) / (xi - xj)
+                let mut next = vec![Ratio::zero(); basis.len() + 1];
+                for (k, c) in basis.iter().enumerate() {
+                    let c = c / denominator;
+                    next[k + 1] += c;
+                    next[k] -= c * i128::from(xj);
+                }
+                basis = next;
+            }
+            for (k, c) in basis.into_iter().enumerate() {
+                coefficients[k] += c;
+            }
+        }
+
+        Self::new(coefficients)
+    }
+
+    fn trim(&mut self) {
+        while self.coefficients.last().is_some_and(Zero::is_zero) {
+            self.coefficients.pop();
+        }
+    }
+
+    /// Coefficients, starting with the constant term. The zero polynomial has none.
+    pub fn coefficients(&self) -> &[Ratio<i128>] {
+        &self.coefficients
+    }
+
+    /// Returns the degree, or [`None`] for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's method.
+    pub fn eval(&self, x: i64) -> Ratio<i128> {
+        let x = Ratio::from_integer(i128::from(x));
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Ratio::zero(), |acc, c| acc * x + c)
+    }
+
+    /// Evaluates the polynomial at `x`, or returns [`None`] if the result is not an integer.
+    pub fn eval_integer(&self, x: i64) -> Option<i128> {
+        let value = self.eval(x);
+        value.is_integer().then(|| value.to_integer())
+    }
+}
+
+impl Display for Polynomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.coefficients.is_empty() {
+            return write!(f, "0");
+        }
+        let terms: Vec<String> = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| !c.is_zero())
+            .map(|(power, c)| match power {
+                0 => format!("{c}"),
+                1 if c.is_one() => "x".to_string(),
+                1 => format!("{c}x"),
+                _ if c.is_one() => format!("x^{power}"),
+                _ => format!("{c}x^{power}"),
+            })
+            .collect();
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{degree, next_value, prev_value, value_at, Polynomial};
+    use num::rational::Ratio;
+
+    #[test]
+    fn extrapolates_sequences() {
+        assert_eq!(next_value(&[0, 3, 6, 9, 12, 15]), 18);
+        assert_eq!(next_value(&[10, 13, 16, 21, 30, 45]), 68);
+        assert_eq!(prev_value(&[10, 13, 16, 21, 30, 45]), 5);
+        assert_eq!(prev_value(&[1, 3, 6, 10, 15, 21]), 0);
+        assert_eq!(next_value(&[1, 3, 6, 10, 15, 21]), 28);
+        assert_eq!(next_value(&[7]), 7);
+    }
+
+    #[test]
+    fn detects_degrees() {
+        assert_eq!(degree(&[5, 5]), Some(0));
+        assert_eq!(degree(&[0, 3, 6, 9]), Some(1));
+        assert_eq!(degree(&[1, 3, 6, 10]), Some(2));
+        assert_eq!(degree(&[1, 3, 6]), None);
+    }
+
+    #[test]
+    fn jumps_ahead() {
+        // triangular numbers, (n + 1)(n + 2) / 2
+        let sequence = [1, 3, 6];
+        assert_eq!(value_at(&sequence, 5), 21);
+        assert_eq!(value_at(&sequence, -1), 0);
+        assert_eq!(value_at(&sequence, 1_000_000), 500_001_500_001);
+    }
+
+    #[test]
+    fn fits_polynomials() {
+        let polynomial = Polynomial::fit(&[(0, 1), (1, 3), (2, 6)]);
+        assert_eq!(polynomial.degree(), Some(2));
+        assert_eq!(
+            polynomial.coefficients(),
+            &[Ratio::from(1), Ratio::new(3, 2), Ratio::new(1, 2)]
+        );
+        assert_eq!(polynomial.to_string(), "1/2x^2 + 3/2x + 1");
+        assert_eq!(polynomial.eval_integer(1_000_000), Some(500_001_500_001));
+        assert_eq!(Polynomial::fit(&[(-1, 2), (1, 2)]).degree(), Some(0));
+        assert_eq!(polynomial.eval(-2), Ratio::from(0));
+    }
+}
@@ -0,0 +1,173 @@
+//! Runtime support for the [`scan!`](crate::scan!) macro.
+//!
+//! A pattern is literal text with `{}` placeholders. Every placeholder matches the shortest text up to the literal
+//! that follows it, or the rest of the line if it is the last one.
+use std::any::type_name;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Why a line did not match a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanError {
+    /// The literal text of the pattern was not found in the input.
+    Mismatch { expected: String, found: String },
+    /// A field could not be parsed into its type.
+    InvalidField {
+        index: usize,
+        field: String,
+        type_name: &'static str,
+    },
+    /// The number of placeholders differs from the number of types.
+    Arity { placeholders: usize, types: usize },
+    /// Two placeholders follow each other without literal text in between, so the fields can not be told apart.
+    AmbiguousPattern(String),
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::Mismatch { expected, found } => {
+                write!(f, "expected {expected:?}, found {found:?}")
+            }
+            ScanError::InvalidField {
+                index,
+                field,
+                type_name,
+            } => write!(f, "field {index} ({field:?}) is not a valid {type_name}"),
+            ScanError::Arity {
+                placeholders,
+                types,
+            } => write!(
+                f,
+                "pattern has {placeholders} placeholders, but {types} types were given"
+            ),
+            ScanError::AmbiguousPattern(pattern) => {
+                write!(f, "pattern {pattern:?} has adjacent placeholders")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// Splits `input` into the text matched by the placeholders of `pattern`.
+pub fn split<'a>(pattern: &str, input: &'a str) -> Result<Vec<&'a str>, ScanError> {
+    let literals: Vec<&str> = pattern.split("{}").collect();
+    let mismatch = |expected: &str, found: &str| ScanError::Mismatch {
+        expected: expected.to_string(),
+        found: found.to_string(),
+    };
+
+    let (first, rest) = literals.split_first().unwrap();
+    let mut remaining = input
+        .strip_prefix(first)
+        .ok_or_else(|| mismatch(first, input))?;
+
+    if rest.is_empty() && !remaining.is_empty() {
+        return Err(mismatch("", remaining));
+    }
+
+    let mut fields = vec![];
+    for (i, literal) in rest.iter().enumerate() {
+        let is_last = i == rest.len() - 1;
+        if is_last {
+            let field = remaining
+                .strip_suffix(literal)
+                .ok_or_else(|| mismatch(literal, remaining))?;
+            fields.push(field);
+        } else if literal.is_empty() {
+            return Err(ScanError::AmbiguousPattern(pattern.to_string()));
+        } else {
+            let (field, after) = remaining
+                .split_once(literal)
+                .ok_or_else(|| mismatch(literal, remaining))?;
+            fields.push(field);
+            remaining = after;
+        }
+    }
+
+    Ok(fields)
+}
+
+/// Parses a field, ignoring surrounding whitespace.
+pub fn field<T: FromStr>(index: usize, text: &str) -> Result<T, ScanError> {
+    text.trim().parse().map_err(|_| ScanError::InvalidField {
+        index,
+        field: text.to_string(),
+        type_name: type_name::<T>(),
+    })
+}
+
+/// Matches a line against a pattern with `{}` placeholders and parses every field into the given type.
+///
+/// Returns a tuple of the fields, or a [`ScanError`](crate::scan::ScanError) describing what did not match.
+/// Any type implementing [`FromStr`] can be used, so fields can be parsed straight into puzzle types.
+#[macro_export]
+macro_rules! scan {
+    ($pattern:expr, $input:expr => $($ty:ty),+ $(,)?) => {
+        $crate::scan::split($pattern, $input).and_then(|fields| {
+            let placeholders = fields.len();
+            let types = [$(stringify!($ty)),+].len();
+            if placeholders != types {
+                return Err($crate::scan::ScanError::Arity { placeholders, types });
+            }
+            let mut fields = fields.into_iter().enumerate();
+            Ok(($({
+                let (index, field) = fields.next().unwrap();
+                $crate::scan::field::<$ty>(index, field)?
+            },)+))
+        })
+    };
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{split, ScanError};
+
+    #[test]
+    fn splits_lines() {
+        assert_eq!(split("{},{}", "1,2"), Ok(vec!["1", "2"]));
+        assert_eq!(split("Game {}: {}", "Game 3: a: b"), Ok(vec!["3", "a: b"]));
+        assert_eq!(split("({})", "(x)"), Ok(vec!["x"]));
+        assert_eq!(split("no fields", "no fields"), Ok(vec![]));
+        assert!(split("no fields", "no fields here").is_err());
+        assert_eq!(
+            split("{}{}", "12"),
+            Err(ScanError::AmbiguousPattern("{}{}".to_string()))
+        );
+        assert_eq!(
+            split("Game {}", "Card 1"),
+            Err(ScanError::Mismatch {
+                expected: "Game ".to_string(),
+                found: "Card 1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_fields() {
+        let (a, b) = crate::scan!("{} -> {}", "a -> 12" => char, i64).unwrap();
+        assert_eq!((a, b), ('a', 12));
+
+        let (x1, _, _, _, y2, z2) =
+            crate::scan!("{},{},{}~{},{},{}", "1,0,1~1,2,1" => i32, i32, i32, i32, i32, i32)
+                .unwrap();
+        assert_eq!((x1, y2, z2), (1, 2, 1));
+        let (dir, steps, color) =
+            crate::scan!("{} {} (#{})", "R 6 (#70c710)" => char, u32, String).unwrap();
+        assert_eq!((dir, steps, color.as_str()), ('R', 6, "70c710"));
+
+        let error = crate::scan!("{},{}", "1,2" => u8).unwrap_err();
+        assert_eq!(
+            error,
+            ScanError::Arity {
+                placeholders: 2,
+                types: 1
+            }
+        );
+        let error = crate::scan!("{},{}", "1,-2" => u8, u8).unwrap_err();
+        assert_eq!(error.to_string(), "field 1 (\"-2\") is not a valid u8");
+        let error = crate::scan!("Game {}: {}", "Game x: 3 blue" => u32, String).unwrap_err();
+        assert_eq!(error.to_string(), "field 0 (\"x\") is not a valid u32");
+    }
+}
@@ -0,0 +1,306 @@
+//! Graph searches over arbitrary states.
+//!
+//! The graph is never built up front: every search takes a `successors` closure that lists the neighbors of a
+//! state, so states can carry whatever the puzzle needs (position, heading, run length, ...).
+//! All searches accept multiple start states, and stop at the first state matching the `is_goal` predicate.
+use num::Zero;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+/// Finds the cheapest cost from any of the start states to a goal state, along with the goal that was reached.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(S, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Zero + Ord + Copy,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(starts, successors, |_| C::zero(), is_goal)
+}
+
+/// Like [`dijkstra`], but returns the states along the cheapest path, starting with the start state.
+pub fn dijkstra_with_path<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Zero + Ord + Copy,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar_with_path(starts, successors, |_| C::zero(), is_goal)
+}
+
+/// Finds the cheapest cost from any of the start states to a goal state, exploring the states that look closest
+/// to a goal first.
+///
+/// `heuristic` estimates the remaining cost to a goal and must never overestimate it, otherwise the result
+/// may not be the cheapest.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(S, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Zero + Ord + Copy,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut found = search(starts, successors, heuristic, is_goal)?;
+    Some((found.states.swap_remove(found.goal), found.cost))
+}
+
+/// Like [`astar`], but returns the states along the cheapest path, starting with the start state.
+pub fn astar_with_path<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Zero + Ord + Copy,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let found = search(starts, successors, heuristic, is_goal)?;
+    let cost = found.cost;
+    Some((found.into_path(), cost))
+}
+
+/// Outcome of [`search`]. States are referred to by their index into `states`.
+struct Found<S, C> {
+    states: Vec<S>,
+    parents: Vec<Option<usize>>,
+    goal: usize,
+    cost: C,
+}
+
+impl<S, C> Found<S, C> {
+    fn into_path(self) -> Vec<S> {
+        let mut indices = vec![self.goal];
+        while let Some(parent) = self.parents[*indices.last().unwrap()] {
+            indices.push(parent);
+        }
+
+        let mut states: Vec<Option<S>> = self.states.into_iter().map(Some).collect();
+        indices
+            .into_iter()
+            .rev()
+            .map(|i| states[i].take().unwrap())
+            .collect()
+    }
+}
+
+fn search<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Zero + Ord + Copy,
+    I: IntoIterator<Item = (S, C)>,
+{
+    // states are stored once and referred to by index, so they don't need to implement `Ord` for the heap.
+    let mut states: Vec<S> = vec![];
+    let mut indices: HashMap<S, usize> = HashMap::new();
+    let mut costs: Vec<C> = vec![];
+    let mut parents: Vec<Option<usize>> = vec![];
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        if indices.contains_key(&start) {
+            continue;
+        }
+        let index = states.len();
+        queue.push(Reverse((heuristic(&start), C::zero(), index)));
+        indices.insert(start.clone(), index);
+        states.push(start);
+        costs.push(C::zero());
+        parents.push(None);
+    }
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        // a cheaper way to this state was found after this entry was queued.
+        if cost > costs[index] {
+            continue;
+        }
+
+        if is_goal(&states[index]) {
+            return Some(Found {
+                states,
+                parents,
+                goal: index,
+                cost,
+            });
+        }
+
+        for (next, step_cost) in successors(&states[index]) {
+            let next_cost = cost + step_cost;
+            let next_index = match indices.get(&next) {
+                Some(&i) if next_cost >= costs[i] => continue,
+                Some(&i) => {
+                    costs[i] = next_cost;
+                    parents[i] = Some(index);
+                    i
+                }
+                None => {
+                    let i = states.len();
+                    indices.insert(next.clone(), i);
+                    states.push(next);
+                    costs.push(next_cost);
+                    parents.push(Some(index));
+                    i
+                }
+            };
+            let priority = next_cost + heuristic(&states[next_index]);
+            queue.push(Reverse((priority, next_cost, next_index)));
+        }
+    }
+
+    None
+}
+
+/// Breadth-first search that yields the states layer by layer, as `(steps, states)`, where every state is first
+/// reached after `steps` steps. Created by [`bfs`].
+pub struct Bfs<S, F> {
+    seen: HashSet<S>,
+    frontier: Vec<S>,
+    steps: usize,
+    successors: F,
+}
+
+/// Starts a breadth-first search from the start states.
+pub fn bfs<S, I, F>(starts: impl IntoIterator<Item = S>, successors: F) -> Bfs<S, F>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+{
+    let mut seen = HashSet::new();
+    let frontier = starts
+        .into_iter()
+        .filter(|s| seen.insert(s.clone()))
+        .collect();
+    Bfs {
+        seen,
+        frontier,
+        steps: 0,
+        successors,
+    }
+}
+
+impl<S, I, F> Iterator for Bfs<S, F>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+{
+    type Item = (usize, Vec<S>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.frontier.is_empty() {
+            return None;
+        }
+
+        let mut next = vec![];
+        for state in &self.frontier {
+            for successor in (self.successors)(state) {
+                if self.seen.insert(successor.clone()) {
+                    next.push(successor);
+                }
+            }
+        }
+
+        let layer = std::mem::replace(&mut self.frontier, next);
+        let steps = self.steps;
+        self.steps += 1;
+        Some((steps, layer))
+    }
+}
+
+/// Returns the number of steps to every state that is reachable from the start states.
+pub fn bfs_distances<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    bfs(starts, successors)
+        .flat_map(|(steps, layer)| layer.into_iter().map(move |s| (s, steps)))
+        .collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{astar, bfs, bfs_distances, dijkstra, dijkstra_with_path};
+
+    /// 0 -1-> 1 -1-> 2 -1-> 3, and a shortcut 0 -5-> 3 that is more expensive.
+    fn edges(x: &u32) -> Vec<(u32, u32)> {
+        match x {
+            0 => vec![(1, 1), (3, 5)],
+            1 => vec![(2, 1)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn finds_cheapest_path() {
+        assert_eq!(dijkstra([0], edges, |x| *x == 3), Some((3, 3)));
+        assert_eq!(
+            dijkstra_with_path([0], edges, |x| *x == 3),
+            Some((vec![0, 1, 2, 3], 3))
+        );
+        assert_eq!(dijkstra([0], edges, |x| *x == 4), None);
+    }
+
+    #[test]
+    fn supports_multiple_starts_and_goals() {
+        assert_eq!(
+            dijkstra_with_path([0, 2], edges, |x| *x == 3),
+            Some((vec![2, 3], 1))
+        );
+        assert_eq!(dijkstra([0], edges, |x| *x >= 2), Some((2, 2)));
+    }
+
+    #[test]
+    fn astar_on_a_grid() {
+        let goal = (5i32, 5i32);
+        let result = astar(
+            [(0i32, 0i32)],
+            |&(x, y)| [((x + 1, y), 1), ((x, y + 1), 1), ((x - 1, y), 1)],
+            |&(x, y)| (goal.0 - x).abs() + (goal.1 - y).abs(),
+            |p| *p == goal,
+        );
+        assert_eq!(result, Some((goal, 10)));
+    }
+
+    #[test]
+    fn walks_bfs_layers() {
+        let layers: Vec<_> = bfs([0], |&x: &i32| {
+            [x - 1, x + 1].into_iter().filter(|x| x.abs() <= 2)
+        })
+        .collect();
+        assert_eq!(
+            layers,
+            vec![(0, vec![0]), (1, vec![-1, 1]), (2, vec![-2, 2])]
+        );
+    }
+
+    #[test]
+    fn counts_bfs_steps() {
+        let distances = bfs_distances([0u32], |x| edges(x).into_iter().map(|(n, _)| n));
+        assert_eq!(distances[&2], 2);
+        assert_eq!(distances[&3], 1);
+    }
+}
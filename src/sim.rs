@@ -0,0 +1,275 @@
+//! Step-by-step simulations: a state and a transition that is applied to it over and over.
+//!
+//! Implement [`Simulation`] for the state and hand it to a [`Driver`], which counts the steps and can
+//! * skip ahead through a loop once a state repeats, see [`Driver::detect_cycles`],
+//! * keep copies of the state at chosen steps to look at or rewind to later, see [`Driver::snapshot_at`],
+//! * record a frame after every step for `--viz`, see [`Driver::animate`].
+use crate::cycle::Cycle;
+use crate::viz::{Animation, Frame};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::Hash;
+
+/// A state that advances in discrete steps.
+pub trait Simulation {
+    /// What identifies a state when looking for cycles, e.g. the state without any counters.
+    type Key: Eq + Hash;
+
+    /// Advances the state by one step.
+    fn step(&mut self);
+
+    /// Returns the key of the current state.
+    fn state_key(&self) -> Self::Key;
+
+    /// Draws the current state.
+    fn render(&self) -> Frame;
+}
+
+/// Runs a [`Simulation`] and keeps track of the steps taken.
+#[derive(Debug)]
+pub struct Driver<S: Simulation> {
+    sim: S,
+    steps: usize,
+    seen: Option<HashMap<S::Key, usize>>,
+    cycle: Option<Cycle>,
+    snapshot_at: BTreeSet<usize>,
+    snapshots: BTreeMap<usize, S>,
+    animation: Option<Animation>,
+}
+
+impl<S: Simulation + Clone> Driver<S> {
+    pub fn new(sim: S) -> Self {
+        Self {
+            sim,
+            steps: 0,
+            seen: None,
+            cycle: None,
+            snapshot_at: BTreeSet::new(),
+            snapshots: BTreeMap::new(),
+            animation: None,
+        }
+    }
+
+    /// Remembers the key of every state, so [`Driver::run`] can skip ahead once a state repeats.
+    #[must_use]
+    pub fn detect_cycles(mut self) -> Self {
+        let mut seen = HashMap::new();
+        seen.insert(self.sim.state_key(), self.steps);
+        self.seen = Some(seen);
+        self
+    }
+
+    /// Keeps a copy of the state whenever one of the given steps is reached, even when skipping through a cycle.
+    #[must_use]
+    pub fn snapshot_at(mut self, steps: impl IntoIterator<Item = usize>) -> Self {
+        self.snapshot_at.extend(steps);
+        self.capture();
+        self
+    }
+
+    /// Adds a frame of the current state, and one after every step, to an animation that is shown by
+    /// [`Driver::finish`]. Frames are only rendered if visualizations are enabled.
+    #[must_use]
+    pub fn animate(mut self) -> Self {
+        let mut animation = Animation::new();
+        animation.push(|| self.sim.render());
+        self.animation = Some(animation);
+        self
+    }
+
+    pub fn sim(&self) -> &S {
+        &self.sim
+    }
+
+    /// Returns the number of steps taken, including the ones skipped because of a cycle.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Returns the cycle, once a state repeated while detecting cycles.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Returns the state captured at `step`, see [`Driver::snapshot_at`].
+    pub fn snapshot(&self, step: usize) -> Option<&S> {
+        self.snapshots.get(&step)
+    }
+
+    /// Advances the simulation by one step.
+    pub fn step(&mut self) {
+        self.sim.step();
+        self.steps += 1;
+
+        if let Some(seen) = &mut self.seen {
+            let start = *seen.entry(self.sim.state_key()).or_insert(self.steps);
+            if start < self.steps && self.cycle.is_none() {
+                self.cycle = Some(Cycle {
+                    start,
+                    period: self.steps - start,
+                });
+            }
+        }
+        self.capture();
+        if let Some(animation) = &mut self.animation {
+            animation.push(|| self.sim.render());
+        }
+    }
+
+    /// Advances the simulation by `n` steps, skipping whole loops once a cycle was detected.
+    pub fn run(&mut self, n: usize) {
+        let target = self.steps + n;
+        while self.steps < target {
+            if let Some(cycle) = self.cycle {
+                // the state repeats every `period` steps from here on, but stop at the next snapshot.
+                let until = self
+                    .snapshot_at
+                    .range(self.steps + 1..=target)
+                    .next()
+                    .map_or(target, |step| *step);
+                let remaining = until - self.steps;
+                self.steps += remaining - remaining % cycle.period;
+                if self.steps == until {
+                    self.capture();
+                    continue;
+                }
+            }
+            self.step();
+        }
+    }
+
+    /// Steps until `done` returns `true` for the state, and returns the number of steps taken by this call.
+    pub fn run_until(&mut self, mut done: impl FnMut(&S) -> bool) -> usize {
+        let start = self.steps;
+        while !done(&self.sim) {
+            self.step();
+        }
+        self.steps - start
+    }
+
+    /// Goes back to the state captured at `step`, or returns `false` if there is no such snapshot.
+    ///
+    /// Cycle detection forgets the states after `step`, so the same cycle is found again when running on.
+    pub fn rewind(&mut self, step: usize) -> bool {
+        let Some(snapshot) = self.snapshots.get(&step) else {
+            return false;
+        };
+        self.sim = snapshot.clone();
+        self.steps = step;
+        if let Some(seen) = &mut self.seen {
+            seen.retain(|_, seen_at| *seen_at <= step);
+        }
+        if self
+            .cycle
+            .is_some_and(|cycle| cycle.start + cycle.period > step)
+        {
+            self.cycle = None;
+        }
+        true
+    }
+
+    /// Shows the animation, if any, and returns the final state.
+    pub fn finish(self) -> S {
+        if let Some(animation) = self.animation {
+            animation.finish();
+        }
+        self.sim
+    }
+
+    fn capture(&mut self) {
+        if self.snapshot_at.contains(&self.steps) {
+            self.snapshots.insert(self.steps, self.sim.clone());
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Driver, Simulation};
+    use crate::cycle::Cycle;
+    use crate::viz::{Cell, Frame};
+
+    /// 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2 -> ..., counting the steps in the state but not in the key
+    #[derive(Debug, Clone)]
+    struct Walk {
+        at: u32,
+        moves: usize,
+    }
+
+    impl Simulation for Walk {
+        type Key = u32;
+
+        fn step(&mut self) {
+            self.at = if self.at == 5 { 2 } else { self.at + 1 };
+            self.moves += 1;
+        }
+
+        fn state_key(&self) -> u32 {
+            self.at
+        }
+
+        fn render(&self) -> Frame {
+            Frame::from_fn(6, 1, |x, _| {
+                Cell::new(if x as u32 == self.at { '@' } else { '.' })
+            })
+        }
+    }
+
+    fn walk() -> Driver<Walk> {
+        Driver::new(Walk { at: 0, moves: 0 })
+    }
+
+    #[test]
+    fn runs_steps() {
+        let mut driver = walk();
+        driver.run(9);
+        assert_eq!(
+            (driver.sim().at, driver.sim().moves, driver.steps()),
+            (5, 9, 9)
+        );
+        assert_eq!(driver.cycle(), None);
+        assert_eq!(driver.run_until(|walk| walk.at == 3), 2);
+        assert_eq!(driver.steps(), 11);
+    }
+
+    #[test]
+    fn skips_cycles() {
+        let mut driver = walk().detect_cycles();
+        driver.run(1_000_000_000);
+        assert_eq!(
+            driver.cycle(),
+            Some(Cycle {
+                start: 2,
+                period: 4
+            })
+        );
+        assert_eq!(driver.sim().at, 2 + (1_000_000_000 - 2) % 4);
+        assert_eq!(driver.steps(), 1_000_000_000);
+        assert!(driver.sim().moves < 20);
+
+        let mut driver = walk().detect_cycles();
+        driver.run(6);
+        driver.run(4);
+        assert_eq!((driver.sim().at, driver.steps()), (2, 10));
+    }
+
+    #[test]
+    fn rewinds_to_snapshots() {
+        let mut driver = walk().detect_cycles().snapshot_at([0, 3, 100, 1001]);
+        driver.run(8);
+        assert!(driver.cycle().is_some());
+        assert_eq!(driver.snapshot(3).map(|walk| walk.at), Some(3));
+        assert!(driver.snapshot(100).is_none());
+
+        assert!(driver.rewind(3));
+        assert_eq!((driver.sim().at, driver.steps()), (3, 3));
+        assert_eq!(driver.cycle(), None);
+        driver.run(97);
+        assert_eq!(driver.snapshot(100).map(|walk| walk.at), Some(2 + 98 % 4));
+        assert!(!driver.rewind(50));
+        driver.run(10_000);
+        assert_eq!(driver.snapshot(1001).map(|walk| walk.at), Some(2 + 999 % 4));
+
+        assert!(driver.rewind(0));
+        assert_eq!(driver.finish().at, 0);
+    }
+}
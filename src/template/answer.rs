@@ -0,0 +1,13 @@
+/// A value that can be returned as the answer to a puzzle part: an integer of any width or a string.
+///
+/// Answers are printed and submitted as they are displayed, so a multi-line string (e.g. letters drawn on a grid)
+/// works as well as a number.
+pub trait Answer: std::fmt::Display {}
+
+macro_rules! impl_answer {
+    ($($t:ty),*) => {
+        $(impl Answer for $t {})*
+    };
+}
+
+impl_answer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, String, &str);
@@ -1,13 +1,23 @@
-use std::io;
+use std::{fmt::Display, fs, io, path::PathBuf, process, process::ExitStatus};
 
+use crate::par::ParOptions;
+use crate::template::commands::solve::Profile;
 use crate::template::{
     readme_benchmarks::{self, Timings},
     ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
 };
 use crate::{all_days, Day};
 
-pub fn handle(is_release: bool, is_timed: bool) {
+pub fn handle(
+    profile: Profile,
+    is_timed: bool,
+    is_alloc: bool,
+    par: ParOptions,
+    json: Option<PathBuf>,
+) {
     let mut timings: Vec<Timings> = vec![];
+    let mut reports: Vec<String> = vec![];
+    let mut failures: Vec<(Day, Error)> = vec![];
 
     all_days().for_each(|day| {
         if day > 1 {
@@ -17,13 +27,21 @@ pub fn handle(is_release: bool, is_timed: bool) {
         println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
         println!("------");
 
-        let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
-
-        if output.is_empty() {
-            println!("Not solved.");
-        } else {
-            let val = child_commands::parse_exec_time(&output, day);
-            timings.push(val);
+        match child_commands::run_solution(day, is_timed, profile, is_alloc, par) {
+            Ok(output) if output.is_empty() => println!("Not solved."),
+            Ok(output) => {
+                let val = child_commands::parse_exec_time(&output, day);
+                timings.push(val);
+                reports.extend(
+                    output
+                        .into_iter()
+                        .filter(|l| child_commands::is_json_report(l)),
+                );
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                failures.push((day, e));
+            }
         }
     });
 
@@ -32,7 +50,8 @@ pub fn handle(is_release: bool, is_timed: bool) {
 
         println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
 
-        if is_release {
+        // overflow checks slow solutions down, so only plain release timings end up in the readme.
+        if profile == Profile::Release {
             match readme_benchmarks::update(timings, total_millis) {
                 Ok(()) => println!("Successfully updated README with benchmarks."),
                 Err(_) => {
@@ -41,6 +60,23 @@ pub fn handle(is_release: bool, is_timed: bool) {
             }
         }
     }
+
+    if let Some(path) = json {
+        let contents = format!("[\n{}\n]\n", reports.join(",\n"));
+        match fs::write(&path, contents) {
+            Ok(()) => println!("Wrote report to {}.", path.display()),
+            Err(e) => eprintln!("Failed to write report to {}: {e}", path.display()),
+        }
+    }
+
+    if !failures.is_empty() {
+        eprintln!();
+        eprintln!("{ANSI_BOLD}Failed:{ANSI_RESET}");
+        for (day, e) in &failures {
+            eprintln!("Day {day}: {e}");
+        }
+        process::exit(1);
+    }
 }
 
 #[derive(Debug)]
@@ -48,6 +84,22 @@ pub enum Error {
     BrokenPipe,
     Parser(String),
     IO(io::Error),
+    CompileFailed,
+    Panicked,
+    BadExitStatus(ExitStatus),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::BrokenPipe => write!(f, "could not capture output of solution."),
+            Error::Parser(s) => write!(f, "could not parse output: {s}"),
+            Error::IO(e) => write!(f, "could not run solution: {e}"),
+            Error::CompileFailed => write!(f, "solution failed to compile."),
+            Error::Panicked => write!(f, "solution panicked."),
+            Error::BadExitStatus(status) => write!(f, "solution exited with {status}."),
+        }
+    }
 }
 
 impl From<std::io::Error> for Error {
@@ -65,33 +117,50 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 mod child_commands {
     use super::{get_path_for_bin, Error};
+    use crate::par::ParOptions;
+    use crate::template::commands::solve::{Profile, PANIC_EXIT_CODE};
     use crate::Day;
     use std::{
         io::{BufRead, BufReader},
         path::Path,
-        process::{Command, Stdio},
+        process::{Command, ExitStatus, Stdio},
         thread,
     };
 
     /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        profile: Profile,
+        is_alloc: bool,
+        par: ParOptions,
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
         }
 
         let day_padded = day.to_string();
+
+        // build separately so that compile errors can be told apart from runtime failures.
+        build_solution(&day_padded, profile, is_alloc)?;
+
         let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        args.extend(profile.cargo_args());
 
-        if is_release {
-            args.push("--release");
+        if is_alloc {
+            args.extend(["--features", "alloc_stats"]);
         }
 
+        // the parts print a JSON report line each, see `is_json_report`.
+        let mut runner_args = par.to_args();
+        runner_args.push("--json".to_string());
         if is_timed {
             // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+            runner_args.push("--time".to_string());
         }
+        args.push("--");
+        args.extend(runner_args.iter().map(String::as_str));
 
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
@@ -115,16 +184,52 @@ mod child_commands {
 
         for line in stdout.lines() {
             let line = line.unwrap();
-            println!("{line}");
+            if !is_json_report(&line) {
+                println!("{line}");
+            }
             output.push(line);
         }
 
         thread.join().unwrap();
-        cmd.wait()?;
+        check_status(cmd.wait()?)?;
 
         Ok(output)
     }
 
+    fn build_solution(day_padded: &str, profile: Profile, is_alloc: bool) -> Result<(), Error> {
+        let mut args = vec!["build", "--quiet", "--bin", day_padded];
+        args.extend(profile.cargo_args());
+
+        if is_alloc {
+            args.extend(["--features", "alloc_stats"]);
+        }
+
+        let status = Command::new("cargo")
+            .args(&args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .status()?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::CompileFailed)
+        }
+    }
+
+    /// Returns `true` for the lines printed by `PartReport::to_json`.
+    pub fn is_json_report(line: &str) -> bool {
+        line.starts_with(r#"{"day":"#)
+    }
+
+    pub fn check_status(status: ExitStatus) -> Result<(), Error> {
+        match status.code() {
+            _ if status.success() => Ok(()),
+            Some(PANIC_EXIT_CODE) => Err(Error::Panicked),
+            _ => Err(Error::BadExitStatus(status)),
+        }
+    }
+
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timings {
         let mut timings = super::Timings {
             day,
@@ -171,7 +276,7 @@ mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -202,7 +307,8 @@ mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{check_status, parse_exec_time};
+        use crate::template::commands::all::Error;
 
         use crate::day;
 
@@ -250,5 +356,22 @@ mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[cfg(unix)]
+        #[test]
+        fn test_exit_status() {
+            use std::os::unix::process::ExitStatusExt;
+            use std::process::ExitStatus;
+
+            assert!(check_status(ExitStatus::from_raw(0)).is_ok());
+            assert!(matches!(
+                check_status(ExitStatus::from_raw(101 << 8)),
+                Err(Error::Panicked)
+            ));
+            assert!(matches!(
+                check_status(ExitStatus::from_raw(1 << 8)),
+                Err(Error::BadExitStatus(_))
+            ));
+        }
     }
 }
@@ -1,14 +1,85 @@
-use std::process::{Command, Stdio};
+use std::io;
+use std::process::{self, Command, ExitStatus, Stdio};
 
+use crate::par::ParOptions;
+use crate::viz::VizOptions;
 use crate::Day;
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+/// Exit code used by the rust runtime when the main thread panics.
+pub const PANIC_EXIT_CODE: i32 = 101;
 
-    if release {
-        cmd_args.push("--release".to_string());
+/// The cargo profile a solution is built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Profile {
+    #[default]
+    Dev,
+    Release,
+    /// Optimized like [`Profile::Release`], but integer overflow panics instead of wrapping around.
+    Checked,
+}
+
+impl Profile {
+    /// Picks the profile for the `--release` and `--checked` flags, where `--checked` wins.
+    #[must_use]
+    pub fn from_flags(release: bool, checked: bool) -> Self {
+        match (release, checked) {
+            (_, true) => Profile::Checked,
+            (true, false) => Profile::Release,
+            (false, false) => Profile::Dev,
+        }
+    }
+
+    /// Returns `true` if the build is optimized, i.e. compiled without debug assertions.
+    #[must_use]
+    pub fn is_optimized(self) -> bool {
+        self != Profile::Dev
+    }
+
+    /// Returns the arguments that select the profile for cargo.
+    #[must_use]
+    pub fn cargo_args(self) -> &'static [&'static str] {
+        match self {
+            Profile::Dev => &[],
+            Profile::Release => &["--release"],
+            Profile::Checked => &["--profile", "checked"],
+        }
+    }
+}
+
+/// Options of the `solve` command that are shared with watch mode.
+#[derive(Debug, Clone, Default)]
+pub struct SolveOptions {
+    pub profile: Profile,
+    /// Bench the solution with `--time`.
+    pub time: bool,
+    /// Count allocations with the `alloc_stats` feature.
+    pub alloc: bool,
+    /// `1` for `-v`, `2` for `-vv`.
+    pub verbosity: u8,
+    /// Only run this part.
+    pub part: Option<u8>,
+    pub viz: Option<VizOptions>,
+    pub par: ParOptions,
+}
+
+pub fn handle(day: Day, options: &SolveOptions, submit_part: Option<u8>) {
+    let features = features(options.profile, options.alloc, options.verbosity);
+
+    // build separately so that compile errors can be told apart from runtime failures.
+    match cargo(&build_args("build", day, options.profile, &features)) {
+        Ok(status) if status.success() => {}
+        Ok(status) => {
+            eprintln!("Day {day} failed: solution failed to compile.");
+            process::exit(status.code().unwrap_or(1));
+        }
+        Err(e) => {
+            eprintln!("failed to call cargo: {e}");
+            process::exit(1);
+        }
     }
 
+    let mut cmd_args = build_args("run", day, options.profile, &features);
+
     cmd_args.push("--".to_string());
 
     if let Some(submit_part) = submit_part {
@@ -16,16 +87,117 @@ pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
-    if time {
-        cmd_args.push("--time".to_string());
+    cmd_args.extend(build_runner_args(
+        options.time,
+        options.verbosity,
+        options.part,
+    ));
+
+    if let Some(viz) = &options.viz {
+        cmd_args.extend(viz.to_args());
+    }
+
+    cmd_args.extend(options.par.to_args());
+
+    match cargo(&cmd_args) {
+        Ok(status) if status.success() => {}
+        Ok(status) if status.code() == Some(PANIC_EXIT_CODE) => {
+            eprintln!("Day {day} failed: solution panicked.");
+            process::exit(PANIC_EXIT_CODE);
+        }
+        Ok(status) => {
+            eprintln!("Day {day} failed: solution exited with {status}.");
+            process::exit(status.code().unwrap_or(1));
+        }
+        Err(e) => {
+            eprintln!("failed to call cargo: {e}");
+            process::exit(1);
+        }
     }
+}
 
-    let mut cmd = Command::new("cargo")
-        .args(&cmd_args)
+fn cargo(args: &[String]) -> io::Result<ExitStatus> {
+    Command::new("cargo")
+        .args(args)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .spawn()
-        .unwrap();
+        .status()
+}
+
+/// Collects the cargo features the solution needs to be built with.
+#[must_use]
+pub fn features(profile: Profile, alloc: bool, verbosity: u8) -> Vec<&'static str> {
+    let mut features = vec![];
+
+    if alloc {
+        features.push("alloc_stats");
+    }
 
-    cmd.wait().unwrap();
+    // log statements are compiled out of optimized builds unless requested.
+    if profile.is_optimized() && verbosity > 0 {
+        features.push("logging");
+    }
+
+    features
+}
+
+/// Builds the arguments for a cargo `command` (`run`, `build`, `test`) targeting the bin of `day`.
+#[must_use]
+pub fn build_args(command: &str, day: Day, profile: Profile, features: &[&str]) -> Vec<String> {
+    let mut cmd_args = vec![command.to_string(), "--bin".to_string(), day.to_string()];
+
+    cmd_args.extend(profile.cargo_args().iter().map(ToString::to_string));
+
+    if !features.is_empty() {
+        cmd_args.push("--features".to_string());
+        cmd_args.push(features.join(","));
+    }
+
+    cmd_args
+}
+
+/// Builds the arguments that are forwarded to the solution binary itself.
+#[must_use]
+pub fn build_runner_args(time: bool, verbosity: u8, part: Option<u8>) -> Vec<String> {
+    let mut cmd_args = vec![];
+
+    match verbosity {
+        0 => {}
+        1 => cmd_args.push("-v".to_string()),
+        _ => cmd_args.push("-vv".to_string()),
+    }
+
+    if let Some(part) = part {
+        cmd_args.push("--part".to_string());
+        cmd_args.push(part.to_string());
+    }
+
+    if time {
+        cmd_args.push("--time".to_string());
+    }
+
+    cmd_args
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{build_args, features, Profile};
+    use crate::day;
+
+    #[test]
+    fn selects_profiles() {
+        assert_eq!(Profile::from_flags(false, false), Profile::Dev);
+        assert_eq!(Profile::from_flags(true, false), Profile::Release);
+        assert_eq!(Profile::from_flags(true, true), Profile::Checked);
+        assert_eq!(
+            build_args("run", day!(1), Profile::Checked, &[]),
+            vec!["run", "--bin", "01", "--profile", "checked"]
+        );
+        assert_eq!(
+            build_args("test", day!(1), Profile::Release, &["logging"]),
+            vec!["test", "--bin", "01", "--release", "--features", "logging"]
+        );
+        assert_eq!(features(Profile::Checked, false, 1), vec!["logging"]);
+        assert!(features(Profile::Dev, false, 1).is_empty());
+    }
 }
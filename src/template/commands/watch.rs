@@ -0,0 +1,245 @@
+/// Watch mode for `solve`: re-runs a day whenever its sources or data files change.
+/// Changes are detected by polling file modification times, so no platform-specific watcher is needed.
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::template::commands::solve::{build_args, build_runner_args, features, SolveOptions};
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::Day;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Answers (or test outcomes) of a single run, keyed by part (or test name).
+type Answers = BTreeMap<String, String>;
+
+type Snapshot = Vec<(PathBuf, Option<SystemTime>)>;
+
+pub fn handle(day: Day, options: &SolveOptions, test: bool) {
+    let features = features(options.profile, options.alloc, options.verbosity);
+
+    let cmd_args = if test {
+        build_args("test", day, options.profile, &features)
+    } else {
+        let mut args = build_args("run", day, options.profile, &features);
+        args.insert(1, "--quiet".to_string());
+        args.push("--".to_string());
+        args.extend(build_runner_args(
+            options.time,
+            options.verbosity,
+            options.part,
+        ));
+        if let Some(viz) = &options.viz {
+            args.extend(viz.to_args());
+        }
+        args.extend(options.par.to_args());
+        args
+    };
+
+    let mut snapshot = take_snapshot(day);
+    let mut previous: Option<Answers> = None;
+
+    loop {
+        println!(
+            "{ANSI_BOLD}[watch]{ANSI_RESET} cargo {}",
+            cmd_args.join(" ")
+        );
+
+        let answers = if test {
+            run_and_collect(&cmd_args, parse_test_outcome)
+        } else {
+            run_and_collect(&cmd_args, parse_answer)
+        };
+
+        if let Some(previous) = &previous {
+            for line in diff_answers(previous, &answers) {
+                println!("{line}");
+            }
+        }
+        previous = Some(answers);
+
+        println!("{ANSI_ITALIC}[watch] waiting for changes...{ANSI_RESET}");
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let next = take_snapshot(day);
+            if next != snapshot {
+                snapshot = next;
+                break;
+            }
+        }
+
+        println!();
+    }
+}
+
+/// Collects the files that trigger a re-run: the day's bin, its data files and all library sources.
+fn watched_files(day: Day) -> Vec<PathBuf> {
+    let mut files = vec![
+        PathBuf::from(format!("src/bin/{day}.rs")),
+        PathBuf::from(format!("data/inputs/{day}.txt")),
+        PathBuf::from(format!("data/examples/{day}.txt")),
+    ];
+    collect_library_sources(Path::new("src"), &mut files);
+    files
+}
+
+fn collect_library_sources(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+        if path.is_dir() {
+            // other days do not affect this one.
+            if path != Path::new("src/bin") {
+                collect_library_sources(&path, files);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+fn take_snapshot(day: Day) -> Snapshot {
+    let mut snapshot: Snapshot = watched_files(day)
+        .into_iter()
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            (path, modified)
+        })
+        .collect();
+    snapshot.sort();
+    snapshot
+}
+
+/// Runs cargo, forwarding its output while collecting the lines recognized by `parse`.
+fn run_and_collect(args: &[String], parse: fn(&str) -> Option<(String, String)>) -> Answers {
+    let mut answers = Answers::new();
+
+    let mut cmd = match Command::new("cargo")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+    {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            eprintln!("failed to call cargo: {e}");
+            return answers;
+        }
+    };
+
+    if let Some(stdout) = cmd.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            println!("{line}");
+            if let Some((key, value)) = parse(&line) {
+                answers.insert(key, value);
+            }
+        }
+    }
+
+    match cmd.wait() {
+        Ok(status) if !status.success() => {
+            eprintln!("{ANSI_BOLD}[watch]{ANSI_RESET} run failed with {status}.");
+        }
+        Err(e) => eprintln!("failed to wait for cargo: {e}"),
+        Ok(_) => {}
+    }
+
+    answers
+}
+
+/// Parses a final result line printed by the runner, e.g. `Part 1: 42 (1.0ms)`.
+fn parse_answer(line: &str) -> Option<(String, String)> {
+    // intermediate results are overwritten with a carriage return.
+    let line = line.rsplit('\r').next()?;
+    let (part, rest) = line.split_once(": ")?;
+
+    if !part.starts_with("Part ") {
+        return None;
+    }
+
+    let answer = match rest.split_once(ANSI_BOLD) {
+        Some((_, rest)) => rest.split(ANSI_RESET).next()?,
+        None => rest.split_whitespace().next()?,
+    };
+
+    Some((part.to_string(), answer.to_string()))
+}
+
+/// Parses a test outcome printed by libtest, e.g. `test tests::test_part_one ... ok`.
+fn parse_test_outcome(line: &str) -> Option<(String, String)> {
+    let (name, outcome) = line.strip_prefix("test ")?.split_once(" ... ")?;
+    Some((name.to_string(), outcome.trim().to_string()))
+}
+
+/// Lists how the answers changed compared to the previous run.
+fn diff_answers(previous: &Answers, next: &Answers) -> Vec<String> {
+    let mut keys: Vec<&String> = previous.keys().chain(next.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .map(|key| match (previous.get(key), next.get(key)) {
+            (Some(a), Some(b)) if a == b => format!("  {key}: {b}"),
+            (Some(a), Some(b)) => format!("~ {key}: {a} -> {ANSI_BOLD}{b}{ANSI_RESET}"),
+            (None, Some(b)) => format!("+ {key}: {ANSI_BOLD}{b}{ANSI_RESET}"),
+            (Some(a), None) => format!("- {key}: {a}"),
+            (None, None) => unreachable!(),
+        })
+        .collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{diff_answers, parse_answer, parse_test_outcome, Answers};
+    use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+    #[test]
+    fn parses_answers() {
+        let line = format!(
+            "Part 1: {ANSI_BOLD}142{ANSI_RESET}\rPart 1: {ANSI_BOLD}142{ANSI_RESET} (17.0µs)"
+        );
+        assert_eq!(parse_answer(&line), Some(("Part 1".into(), "142".into())));
+        assert_eq!(
+            parse_answer("Part 2: ✖             "),
+            Some(("Part 2".into(), "✖".into()))
+        );
+        assert_eq!(parse_answer("directions repeat every 3 steps"), None);
+    }
+
+    #[test]
+    fn parses_test_outcomes() {
+        assert_eq!(
+            parse_test_outcome("test tests::test_part_one ... ok"),
+            Some(("tests::test_part_one".into(), "ok".into()))
+        );
+        assert_eq!(parse_test_outcome("running 2 tests"), None);
+    }
+
+    #[test]
+    fn diffs_answers() {
+        let previous = Answers::from([
+            ("Part 1".into(), "100".into()),
+            ("Part 2".into(), "42".into()),
+        ]);
+        let next = Answers::from([
+            ("Part 1".into(), "142".into()),
+            ("Part 2".into(), "42".into()),
+        ]);
+        assert_eq!(
+            diff_answers(&previous, &next),
+            vec![
+                format!("~ Part 1: 100 -> {ANSI_BOLD}142{ANSI_RESET}"),
+                "  Part 2: 42".to_string(),
+            ]
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::Day;
 use std::{env, fs};
 
+pub mod alloc_stats;
+pub mod answer;
 pub mod aoc_cli;
 pub mod commands;
 pub mod readme_benchmarks;
@@ -28,6 +30,9 @@ macro_rules! solution {
 
         fn main() {
             use advent_of_code::template::runner::*;
+            advent_of_code::log::init_from_args();
+            advent_of_code::viz::init_from_args();
+            advent_of_code::par::init_from_args();
             let input = advent_of_code::template::read_file("inputs", DAY);
             run_part(part_one, &input, DAY, 1);
             run_part(part_two, &input, DAY, 2);
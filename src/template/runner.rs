@@ -1,66 +1,271 @@
 /// Encapsulates code that interacts with solution functions.
+use crate::template::alloc_stats::{self, AllocStats};
+use crate::template::answer::Answer;
 use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
-use crate::Day;
+use crate::{log, Day};
+use std::any::Any;
 use std::fmt::Display;
 use std::io::{stdout, Write};
+use std::panic::{self, AssertUnwindSafe};
 use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
 use super::ANSI_BOLD;
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Options for [`run`].
+#[derive(Debug, Clone, Copy)]
+pub struct RunOptions {
+    /// Bench the function after the first execution (approx. 1 second of execution time or 10 samples, whatever take longer.)
+    pub bench: bool,
+    /// Report a panicking solution as [`PartStatus::Panicked`] instead of unwinding into the caller.
+    pub catch_panics: bool,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            bench: false,
+            catch_panics: true,
+        }
+    }
+}
+
+impl RunOptions {
+    /// Reads the options from the arguments passed to a solution binary.
+    #[must_use]
+    pub fn from_args() -> Self {
+        Self {
+            bench: env::args().any(|x| x == "--time"),
+            catch_panics: false,
+        }
+    }
+}
+
+/// Outcome of running a solution part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartStatus {
+    Solved,
+    /// The solution returned [`None`].
+    Unsolved,
+    /// The solution panicked with the contained message.
+    Panicked(String),
+}
+
+/// The result of running a solution part with [`run`].
+#[derive(Debug, Clone)]
+pub struct PartReport {
+    pub day: Day,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub status: PartStatus,
+    /// Execution time of the first run, or the average execution time if the part was benched.
+    pub duration: Duration,
+    pub samples: u128,
+    /// Allocations of the first run, if the `alloc_stats` feature is enabled.
+    pub allocs: Option<AllocStats>,
+}
+
+impl PartReport {
+    /// Serializes the report as a single line of JSON, e.g.
+    /// `{"day":1,"part":1,"status":"solved","answer":"42","duration_nanos":166,"samples":1,"allocs":null}`.
+    ///
+    /// `allocs` holds `allocations`, `bytes_allocated` and `peak_live_bytes` if the `alloc_stats` feature is enabled.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let (status, message) = match &self.status {
+            PartStatus::Solved => ("solved", None),
+            PartStatus::Unsolved => ("unsolved", None),
+            PartStatus::Panicked(message) => ("panicked", Some(message)),
+        };
+        let string_or_null = |s: Option<&String>| s.map_or("null".to_string(), |s| json_string(s));
+        let allocs = self.allocs.map_or("null".to_string(), |a| {
+            format!(
+                r#"{{"allocations":{},"bytes_allocated":{},"peak_live_bytes":{}}}"#,
+                a.allocations, a.bytes_allocated, a.peak_live_bytes
+            )
+        });
+
+        let mut json = format!(
+            r#"{{"day":{},"part":{},"status":"{status}","answer":{}"#,
+            self.day.into_inner(),
+            self.part,
+            string_or_null(self.answer.as_ref()),
+        );
+        if let Some(message) = message {
+            json.push_str(&format!(r#","message":{}"#, json_string(message)));
+        }
+        json.push_str(&format!(
+            r#","duration_nanos":{},"samples":{},"allocs":{allocs}}}"#,
+            self.duration.as_nanos(),
+            self.samples,
+        ));
+        json
+    }
+}
+
+/// Quotes a string for JSON, escaping quotes, backslashes and control characters.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Runs a solution part and reports its answer, timings and allocations without printing anything.
+pub fn run<I: Clone, T: Answer>(
+    day: Day,
+    part: u8,
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    options: &RunOptions,
+) -> PartReport {
+    run_with_hook(day, part, func, input, options, |_| {})
+}
+
+pub fn run_part<I: Clone, T: Answer>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+    if !is_selected_part(part) {
+        return;
+    }
+
     let part_str = format!("Part {part}");
+    let options = RunOptions::from_args();
+
+    let report = run_with_hook(day, part, func, input, &options, |answer| {
+        print_result(answer, &part_str, "");
+        if options.bench {
+            print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+            let _ = stdout().flush();
+        }
+    });
+
+    print_report(&report);
+
+    // `all --json` collects these lines into a report file.
+    if env::args().any(|x| x == "--json") {
+        println!("{}", report.to_json());
+    }
+
+    if let Some(answer) = report.answer {
+        submit_result(answer, day, part);
+    }
+}
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+/// Prints a report in the format `Part 1: 42 (1.0ms @ 10 samples)`.
+pub fn print_report(report: &PartReport) {
+    let part_str = format!("Part {}", report.part);
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    let stats_str = format!(
+        "{}{}",
+        format_duration(&report.duration, report.samples),
+        report.allocs.map(|a| format_allocs(&a)).unwrap_or_default()
+    );
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    match &report.status {
+        PartStatus::Panicked(message) => {
+            print!("\r");
+            println!("{part_str}: ✖ panicked: {message}");
+        }
+        _ => print_result(&report.answer, &part_str, &stats_str),
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
+///
+/// Allocations are only recorded for the first execution, and only if the `alloc_stats` feature is enabled.
+/// `hook` is called with the answer after the first execution.
+fn run_with_hook<I: Clone, T: Answer>(
+    day: Day,
+    part: u8,
+    func: impl Fn(I) -> Option<T>,
     input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
-    let timer = Instant::now();
-    let result = func(input.clone());
-    let base_time = timer.elapsed();
+    options: &RunOptions,
+    hook: impl Fn(&Option<String>),
+) -> PartReport {
+    let cloned = input.clone();
+    let first_run = || {
+        alloc_stats::measure(|| {
+            let timer = Instant::now();
+            let result = func(cloned);
+            (result, timer.elapsed())
+        })
+    };
+
+    let outcome = if options.catch_panics {
+        panic::catch_unwind(AssertUnwindSafe(first_run)).map_err(|e| panic_message(&*e))
+    } else {
+        Ok(first_run())
+    };
 
-    hook(&result);
+    let ((result, base_time), allocs) = match outcome {
+        Ok(outcome) => outcome,
+        Err(message) => {
+            return PartReport {
+                day,
+                part,
+                answer: None,
+                status: PartStatus::Panicked(message),
+                duration: Duration::ZERO,
+                samples: 0,
+                allocs: None,
+            };
+        }
+    };
+
+    let answer = result.map(|r| r.to_string());
+    hook(&answer);
 
-    let run = if std::env::args().any(|x| x == "--time") {
+    let (duration, samples) = if options.bench {
         bench(func, input, &base_time)
     } else {
         (base_time, 1)
     };
 
-    (result, run.0, run.1)
+    PartReport {
+        day,
+        part,
+        status: if answer.is_some() {
+            PartStatus::Solved
+        } else {
+            PartStatus::Unsolved
+        },
+        answer,
+        duration,
+        samples,
+        allocs,
+    }
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
-    let mut stdout = stdout();
-
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+    let bench_iterations =
+        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
     let mut timers: Vec<Duration> = vec![];
 
+    // solutions would otherwise log once per iteration and distort the timings.
+    let _muted = log::mute();
+
     for _ in 0..bench_iterations {
         // need a clone here to make the borrow checker happy.
         let cloned = input.clone();
@@ -92,6 +297,15 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
+fn format_allocs(allocs: &AllocStats) -> String {
+    format!(
+        " [{} allocs, {}, peak {}]",
+        allocs.allocations,
+        alloc_stats::format_bytes(allocs.bytes_allocated),
+        alloc_stats::format_bytes(allocs.peak_live_bytes)
+    )
+}
+
 fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
@@ -127,6 +341,16 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
     }
 }
 
+/// Parse the `--part` argument passed to `solve`. All parts are selected if it is absent.
+fn is_selected_part(part: u8) -> bool {
+    let args: Vec<String> = env::args().collect();
+
+    match args.iter().position(|x| x == "--part") {
+        Some(index) => args.get(index + 1).and_then(|x| x.parse::<u8>().ok()) == Some(part),
+        None => true,
+    }
+}
+
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
@@ -165,3 +389,91 @@ fn submit_result<T: Display>(
     println!("Submitting result via aoc-cli...");
     Some(aoc_cli::submit(day, part, &result.to_string()))
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{run, AllocStats, PartStatus, RunOptions};
+    use crate::day;
+    use std::time::Duration;
+
+    #[test]
+    fn reports_answers() {
+        let report = run(day!(1), 1, |x: u32| Some(x * 2), 21, &RunOptions::default());
+        assert_eq!(report.answer.as_deref(), Some("42"));
+        assert_eq!(report.status, PartStatus::Solved);
+        assert_eq!(report.samples, 1);
+    }
+
+    #[test]
+    fn reports_wide_and_text_answers() {
+        let options = RunOptions::default();
+        let report = run(day!(1), 1, |x: u64| Some(x << 40), 3, &options);
+        assert_eq!(report.answer.as_deref(), Some("3298534883328"));
+        let report = run(day!(1), 2, |x: i128| Some(-x), 7, &options);
+        assert_eq!(report.answer.as_deref(), Some("-7"));
+        let report = run(day!(1), 2, |x: &str| Some(x.to_uppercase()), "ab", &options);
+        assert_eq!(report.answer.as_deref(), Some("AB"));
+    }
+
+    #[test]
+    fn serializes_reports() {
+        let mut report = run(day!(3), 2, |x: u32| Some(x), 7, &RunOptions::default());
+        report.duration = Duration::from_nanos(1500);
+        report.allocs = Some(AllocStats {
+            allocations: 2,
+            bytes_allocated: 64,
+            peak_live_bytes: 32,
+        });
+        assert_eq!(
+            report.to_json(),
+            r#"{"day":3,"part":2,"status":"solved","answer":"7","duration_nanos":1500,"samples":1,"allocs":{"allocations":2,"bytes_allocated":64,"peak_live_bytes":32}}"#
+        );
+
+        let report = run(
+            day!(3),
+            1,
+            |_: &str| -> Option<u32> { panic!("bad \"input\"\n") },
+            "",
+            &RunOptions::default(),
+        );
+        assert!(report
+            .to_json()
+            .contains(r#""answer":null,"message":"bad \"input\"\n","duration_nanos":0"#));
+        assert!(report.to_json().ends_with(r#""allocs":null}"#));
+    }
+
+    #[test]
+    fn reports_unsolved() {
+        let report = run(
+            day!(1),
+            2,
+            |_: &str| None::<u32>,
+            "",
+            &RunOptions::default(),
+        );
+        assert_eq!(report.answer, None);
+        assert_eq!(report.status, PartStatus::Unsolved);
+    }
+
+    #[test]
+    fn reports_panics() {
+        let report = run(
+            day!(1),
+            1,
+            |_: &str| -> Option<u32> { panic!("not yet") },
+            "",
+            &RunOptions::default(),
+        );
+        assert_eq!(report.status, PartStatus::Panicked("not yet".into()));
+    }
+
+    #[test]
+    fn benches() {
+        let options = RunOptions {
+            bench: true,
+            ..RunOptions::default()
+        };
+        let report = run(day!(1), 1, |x: u64| Some(x + 1), 1, &options);
+        assert!(report.samples >= 10);
+    }
+}
@@ -0,0 +1,251 @@
+//! Minimal image encoders for exporting frames: PPM, PNG (uncompressed) and animated GIF.
+//!
+//! The encoders favor simplicity over file size, which is fine for puzzle-sized images and avoids pulling in
+//! image crates.
+use super::Rgb;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// An RGB image, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Creates an image with every pixel set to `color`.
+    pub fn new(width: usize, height: usize, color: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    /// Writes the image as a binary PPM (`P6`).
+    pub fn write_ppm(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for pixel in &self.pixels {
+            out.write_all(&[pixel.0, pixel.1, pixel.2])?;
+        }
+        out.flush()
+    }
+
+    /// Writes the image as a PNG with uncompressed image data.
+    pub fn write_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(&self.to_png())?;
+        out.flush()
+    }
+
+    fn to_png(&self) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+
+        let mut header = vec![];
+        header.extend(to_u32(self.width).to_be_bytes());
+        header.extend(to_u32(self.height).to_be_bytes());
+        // 8 bit RGB, default compression and filtering, not interlaced.
+        header.extend([8, 2, 0, 0, 0]);
+        write_chunk(&mut png, b"IHDR", &header);
+
+        // every scanline starts with its filter type, which is 0 (none).
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            raw.push(0);
+            raw.extend(row.iter().flat_map(|p| [p.0, p.1, p.2]));
+        }
+        write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        write_chunk(&mut png, b"IEND", &[]);
+
+        png
+    }
+}
+
+fn to_u32(value: usize) -> u32 {
+    u32::try_from(value).expect("image is too large")
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend(to_u32(data.len()).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Wraps the data into a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = data.chunks(u16::MAX as usize).collect();
+    if blocks.is_empty() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    for (i, block) in blocks.iter().enumerate() {
+        let is_last = i == blocks.len() - 1;
+        out.push(u8::from(is_last));
+        let len = block.len() as u16;
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(*block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + u32::from(*byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    out.extend(((b << 16) | a).to_be_bytes());
+    out
+}
+
+/// Writes the frames as a looping GIF, showing every frame for `delay_cs` hundredths of a second.
+///
+/// All frames must have the size of the first one, and may use at most 256 colors in total.
+pub fn write_gif(path: impl AsRef<Path>, frames: &[Image], delay_cs: u16) -> io::Result<()> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidInput, message.to_string());
+    let first = frames
+        .first()
+        .ok_or_else(|| invalid("no frames to write"))?;
+    if frames
+        .iter()
+        .any(|f| f.width != first.width || f.height != first.height)
+    {
+        return Err(invalid("frames differ in size"));
+    }
+
+    let mut palette: HashMap<Rgb, u8> = HashMap::new();
+    let mut colors = vec![];
+    for pixel in frames.iter().flat_map(|f| &f.pixels) {
+        if !palette.contains_key(pixel) {
+            let index = u8::try_from(colors.len())
+                .map_err(|_| invalid("a GIF can not have more than 256 colors"))?;
+            palette.insert(*pixel, index);
+            colors.push(*pixel);
+        }
+    }
+    colors.resize(256, Rgb(0, 0, 0));
+
+    let width = u16::try_from(first.width).map_err(|_| invalid("image is too wide"))?;
+    let height = u16::try_from(first.height).map_err(|_| invalid("image is too high"))?;
+
+    let mut out = BufWriter::new(File::create(path)?);
+    out.write_all(b"GIF89a")?;
+    out.write_all(&width.to_le_bytes())?;
+    out.write_all(&height.to_le_bytes())?;
+    // global color table with 256 entries, 8 bits per channel.
+    out.write_all(&[0xf7, 0, 0])?;
+    for color in &colors {
+        out.write_all(&[color.0, color.1, color.2])?;
+    }
+    // loop forever.
+    out.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+    for frame in frames {
+        out.write_all(&[0x21, 0xf9, 0x04, 0x00])?;
+        out.write_all(&delay_cs.to_le_bytes())?;
+        out.write_all(&[0x00, 0x00])?;
+
+        out.write_all(&[0x2c, 0, 0, 0, 0])?;
+        out.write_all(&width.to_le_bytes())?;
+        out.write_all(&height.to_le_bytes())?;
+        out.write_all(&[0x00])?;
+
+        let indices: Vec<u8> = frame.pixels.iter().map(|p| palette[p]).collect();
+        out.write_all(&[8])?;
+        for block in lzw_uncompressed(&indices).chunks(255) {
+            out.write_all(&[block.len() as u8])?;
+            out.write_all(block)?;
+        }
+        out.write_all(&[0x00])?;
+    }
+
+    out.write_all(&[0x3b])?;
+    out.flush()
+}
+
+/// Encodes 8 bit indices as GIF LZW codes without actually compressing them.
+///
+/// Every index is emitted as a literal 9 bit code. The decoder still grows its table after each code, so a clear
+/// code is sent before the table would require 10 bit codes.
+fn lzw_uncompressed(indices: &[u8]) -> Vec<u8> {
+    const CLEAR: u32 = 256;
+    const END: u32 = 257;
+
+    let mut bytes = vec![];
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    let mut emit = |code: u32| {
+        buffer |= code << bits;
+        bits += 9;
+        while bits >= 8 {
+            bytes.push(buffer as u8);
+            buffer >>= 8;
+            bits -= 8;
+        }
+    };
+
+    for chunk in indices.chunks(254) {
+        emit(CLEAR);
+        for index in chunk {
+            emit(u32::from(*index));
+        }
+    }
+    emit(END);
+    if bits > 0 {
+        bytes.push(buffer as u8);
+    }
+    bytes
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{crc32, lzw_uncompressed, zlib_stored, Image};
+    use crate::viz::Rgb;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        // adler32 of "Wikipedia" is 0x11E60398.
+        assert!(zlib_stored(b"Wikipedia").ends_with(&[0x11, 0xe6, 0x03, 0x98]));
+    }
+
+    #[test]
+    fn encodes_png() {
+        let mut image = Image::new(2, 1, Rgb(0, 0, 0));
+        image.set(1, 0, Rgb(255, 0, 0));
+        let png = image.to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR"));
+        assert!(png.ends_with(b"IEND\xae\x42\x60\x82"));
+    }
+
+    #[test]
+    fn encodes_lzw_codes() {
+        // clear (256), 1, end (257) as 9 bit codes, least significant bit first.
+        assert_eq!(lzw_uncompressed(&[1]), vec![0x00, 0x03, 0x04, 0x04]);
+    }
+}
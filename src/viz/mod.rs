@@ -0,0 +1,445 @@
+//! Visualizations of grids and simulations.
+//!
+//! Build a [`Frame`] of styled cells, then [`show`] it or collect several into an [`Animation`].
+//! Nothing is rendered unless `solve` was called with `--viz`, and frames are not even built in that case since
+//! they are passed as closures. Visualizations are always off when a solution is benched with `--time`.
+//!
+//! * `--viz` plays animations in the terminal.
+//! * `--viz-fps <n>` sets the speed of animations (default: 10 frames per second).
+//! * `--viz-export <dir>` writes the frames to `dir` as PNG images plus an animated GIF instead.
+mod image;
+
+pub use image::{write_gif, Image};
+
+use crate::grid::Grid;
+use std::fmt::{Display, Write as _};
+use std::io::{stderr, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::{env, fs, thread};
+
+/// An RGB color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GRAY: Rgb = Rgb(128, 128, 128);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(133, 153, 0);
+    pub const YELLOW: Rgb = Rgb(255, 215, 0);
+    pub const BLUE: Rgb = Rgb(38, 139, 210);
+}
+
+/// How a cell is drawn. Unset colors use the terminal defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub fg: Option<Rgb>,
+    pub bg: Option<Rgb>,
+    pub bold: bool,
+}
+
+impl Style {
+    #[must_use]
+    pub fn fg(mut self, color: Rgb) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    #[must_use]
+    pub fn bg(mut self, color: Rgb) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    #[must_use]
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Applies the colors and weight that are set in `overlay` on top of this style.
+    #[must_use]
+    pub fn overlay(self, overlay: Style) -> Self {
+        Self {
+            fg: overlay.fg.or(self.fg),
+            bg: overlay.bg.or(self.bg),
+            bold: self.bold || overlay.bold,
+        }
+    }
+
+    fn ansi(&self) -> String {
+        let mut codes = String::new();
+        if self.bold {
+            codes.push_str("\x1b[1m");
+        }
+        if let Some(Rgb(r, g, b)) = self.fg {
+            let _ = write!(codes, "\x1b[38;2;{r};{g};{b}m");
+        }
+        if let Some(Rgb(r, g, b)) = self.bg {
+            let _ = write!(codes, "\x1b[48;2;{r};{g};{b}m");
+        }
+        codes
+    }
+}
+
+/// A character drawn in a style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub style: Style,
+}
+
+impl Cell {
+    pub fn new(ch: char) -> Self {
+        Self {
+            ch,
+            style: Style::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn fg(mut self, color: Rgb) -> Self {
+        self.style = self.style.fg(color);
+        self
+    }
+
+    #[must_use]
+    pub fn bg(mut self, color: Rgb) -> Self {
+        self.style = self.style.bg(color);
+        self
+    }
+
+    #[must_use]
+    pub fn bold(mut self) -> Self {
+        self.style = self.style.bold();
+        self
+    }
+
+    /// The color of the cell in an image: its background, else its foreground, else white for visible characters.
+    fn color(&self) -> Rgb {
+        self.style
+            .bg
+            .or(self.style.fg)
+            .unwrap_or(if self.ch == ' ' || self.ch == '.' {
+                Rgb::BLACK
+            } else {
+                Rgb::WHITE
+            })
+    }
+}
+
+impl From<char> for Cell {
+    fn from(ch: char) -> Self {
+        Self::new(ch)
+    }
+}
+
+/// A grid of styled cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    cells: Grid<Cell>,
+}
+
+impl Frame {
+    /// Creates a frame by drawing every position.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> Cell) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Self {
+            cells: Grid::from_vec(width, height, cells),
+        }
+    }
+
+    /// Creates a frame by drawing every cell of a grid.
+    pub fn from_grid<T>(grid: &Grid<T>, f: impl FnMut(&T) -> Cell) -> Self {
+        Self { cells: grid.map(f) }
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    /// Replaces the cell at a position, ignoring positions outside the frame.
+    pub fn set(&mut self, x: isize, y: isize, cell: Cell) {
+        if let Some(target) = self.cells.get_mut(x, y) {
+            *target = cell;
+        }
+    }
+
+    /// Draws `style` over the cells at the positions, ignoring positions outside the frame.
+    pub fn highlight(
+        &mut self,
+        positions: impl IntoIterator<Item = (isize, isize)>,
+        style: Style,
+    ) -> &mut Self {
+        for (x, y) in positions {
+            if let Some(cell) = self.cells.get_mut(x, y) {
+                cell.style = cell.style.overlay(style);
+            }
+        }
+        self
+    }
+
+    /// Renders the frame with ANSI escape codes for a true-color terminal.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for row in self.cells.rows() {
+            let mut current = Style::default();
+            for cell in row {
+                if cell.style != current {
+                    out.push_str("\x1b[0m");
+                    out.push_str(&cell.style.ansi());
+                    current = cell.style;
+                }
+                out.push(cell.ch);
+            }
+            out.push_str("\x1b[0m\n");
+        }
+        out
+    }
+
+    /// Renders the frame as an image, drawing every cell as a `scale` x `scale` square.
+    pub fn to_image(&self, scale: usize) -> Image {
+        let mut image = Image::new(self.width() * scale, self.height() * scale, Rgb::BLACK);
+        for ((x, y), cell) in self.cells.iter() {
+            let color = cell.color();
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    image.set(x * scale + dx, y * scale + dy, color);
+                }
+            }
+        }
+        image
+    }
+}
+
+/// Prints the characters without styles.
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.rows() {
+            for cell in row {
+                f.write_char(cell.ch)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Settings read from the `--viz` arguments of a solution binary.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VizOptions {
+    pub fps: Option<u32>,
+    pub export: Option<PathBuf>,
+}
+
+impl VizOptions {
+    /// Builds the arguments that are forwarded to a solution binary.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec!["--viz".to_string()];
+        if let Some(fps) = self.fps {
+            args.push("--viz-fps".to_string());
+            args.push(fps.to_string());
+        }
+        if let Some(export) = &self.export {
+            args.push("--viz-export".to_string());
+            args.push(export.display().to_string());
+        }
+        args
+    }
+
+    fn from_args(args: &[String]) -> Option<Self> {
+        let value = |name: &str| {
+            args.iter()
+                .position(|arg| arg == name)
+                .and_then(|i| args.get(i + 1))
+        };
+        let options = Self {
+            fps: value("--viz-fps").and_then(|fps| fps.parse().ok()),
+            export: value("--viz-export").map(PathBuf::from),
+        };
+
+        // never slow down benchmarks.
+        let requested = args.iter().any(|arg| arg == "--viz")
+            || options.fps.is_some()
+            || options.export.is_some();
+        let benched = args.iter().any(|arg| arg == "--time");
+        (requested && !benched).then_some(options)
+    }
+}
+
+static OPTIONS: OnceLock<Option<VizOptions>> = OnceLock::new();
+static EXPORTS: AtomicU32 = AtomicU32::new(0);
+
+/// Reads the `--viz` arguments. Called by the `solution!` macro; visualizations stay off if this is not called.
+pub fn init_from_args() {
+    let args: Vec<String> = env::args().collect();
+    let _ = OPTIONS.set(VizOptions::from_args(&args));
+}
+
+fn options() -> Option<&'static VizOptions> {
+    OPTIONS.get().and_then(Option::as_ref)
+}
+
+/// Returns `true` if visualizations were requested with `--viz`.
+pub fn enabled() -> bool {
+    options().is_some()
+}
+
+/// Shows a single frame, if visualizations are enabled.
+pub fn show(frame: impl FnOnce() -> Frame) {
+    let mut animation = Animation::new();
+    animation.push(frame);
+    animation.finish();
+}
+
+/// A sequence of frames, played back or exported by [`Animation::finish`].
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    frames: Vec<Frame>,
+    fps: Option<u32>,
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the playback speed, unless it is overridden with `--viz-fps`.
+    #[must_use]
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.fps = Some(fps);
+        self
+    }
+
+    fn fps(&self) -> u32 {
+        options()
+            .and_then(|o| o.fps)
+            .or(self.fps)
+            .unwrap_or(10)
+            .max(1)
+    }
+
+    /// Adds a frame. The closure is only called if visualizations are enabled.
+    pub fn push(&mut self, frame: impl FnOnce() -> Frame) {
+        if enabled() {
+            self.frames.push(frame());
+        }
+    }
+
+    /// Plays the animation in the terminal, or exports it if `--viz-export` was given.
+    pub fn finish(self) {
+        let Some(options) = options() else {
+            return;
+        };
+        if self.frames.is_empty() {
+            return;
+        }
+
+        match &options.export {
+            Some(dir) => {
+                if let Err(e) = self.export(dir) {
+                    eprintln!("failed to export visualization to {}: {e}", dir.display());
+                }
+            }
+            None => self.play(),
+        }
+    }
+
+    fn play(&self) {
+        let delay = Duration::from_secs_f64(1.0 / f64::from(self.fps()));
+        let mut out = stderr().lock();
+        for (i, frame) in self.frames.iter().enumerate() {
+            // move the cursor back up to draw the next frame over the previous one.
+            if i > 0 {
+                let _ = write!(out, "\x1b[{}A", frame.height());
+            }
+            let _ = write!(out, "{}", frame.to_ansi());
+            let _ = out.flush();
+            if i + 1 < self.frames.len() {
+                thread::sleep(delay);
+            }
+        }
+    }
+
+    /// Writes every frame as a PNG image and all of them as an animated GIF, numbered per animation of the run.
+    fn export(&self, dir: &Path) -> std::io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let id = EXPORTS.fetch_add(1, Ordering::Relaxed);
+
+        // scale small grids up so the images are not tiny.
+        let largest = self
+            .frames
+            .iter()
+            .map(|f| f.width().max(f.height()))
+            .max()
+            .unwrap_or(1);
+        let scale = (512 / largest.max(1)).clamp(1, 16);
+
+        let images: Vec<Image> = self.frames.iter().map(|f| f.to_image(scale)).collect();
+        for (i, image) in images.iter().enumerate() {
+            image.write_png(dir.join(format!("{id:02}-{i:04}.png")))?;
+        }
+        if images.len() > 1 {
+            let delay_cs = (100 / self.fps()).max(1) as u16;
+            write_gif(dir.join(format!("{id:02}.gif")), &images, delay_cs)?;
+        }
+
+        eprintln!("wrote {} frame(s) to {}", self.frames.len(), dir.display());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Cell, Frame, Rgb, Style, VizOptions};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn renders_frames() {
+        let mut frame = Frame::from_fn(2, 2, |x, y| Cell::new(if x == y { '#' } else { '.' }));
+        assert_eq!(frame.to_string(), "#.\n.#\n");
+
+        frame.highlight([(1, 0), (5, 5)], Style::default().fg(Rgb::RED));
+        let ansi = frame.to_ansi();
+        assert!(ansi.starts_with("#\x1b[0m\x1b[38;2;220;50;47m.\x1b[0m\n"));
+
+        let image = frame.to_image(2);
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.pixels[0], Rgb::WHITE);
+        assert_eq!(image.pixels[2], Rgb::RED);
+    }
+
+    #[test]
+    fn reads_arguments() {
+        assert_eq!(VizOptions::from_args(&args(&["01"])), None);
+        assert_eq!(
+            VizOptions::from_args(&args(&["01", "--viz"])),
+            Some(VizOptions::default())
+        );
+        let options = VizOptions::from_args(&args(&["01", "--viz-fps", "30"])).unwrap();
+        assert_eq!(options.fps, Some(30));
+        assert_eq!(
+            VizOptions::from_args(&args(&["01", "--viz", "--time"])),
+            None
+        );
+        assert_eq!(
+            VizOptions::from_args(&options.to_args()),
+            Some(options.clone())
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Axis-aligned boxes on the integer lattice, sparse voxel occupancy and settling of stacked boxes.
+//!
+//! Boxes are inclusive on both ends, like the `x1,y1,z1~x2,y2,z2` bricks of puzzle inputs, so a single cube has
+//! `min == max`. `z` points up.
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use glam::IVec3;
+
+/// An axis-aligned box, containing all cells from `min` to `max` inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Aabb {
+    pub min: IVec3,
+    pub max: IVec3,
+}
+
+impl Aabb {
+    /// Creates the box spanned by two opposite corners, in any order.
+    pub fn new(a: IVec3, b: IVec3) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// Returns the number of cells along each axis.
+    pub fn size(&self) -> IVec3 {
+        self.max - self.min + IVec3::ONE
+    }
+
+    /// Returns the number of cells in the box.
+    pub fn volume(&self) -> i64 {
+        let size = self.size().as_i64vec3();
+        size.x * size.y * size.z
+    }
+
+    pub fn contains(&self, cell: IVec3) -> bool {
+        cell.cmpge(self.min).all() && cell.cmple(self.max).all()
+    }
+
+    /// Returns whether the boxes share at least one cell.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    /// Returns whether the boxes would overlap when viewed from above, i.e. ignoring `z`.
+    pub fn overlaps_xy(&self, other: &Self) -> bool {
+        self.min.truncate().cmple(other.max.truncate()).all()
+            && other.min.truncate().cmple(self.max.truncate()).all()
+    }
+
+    /// Returns the cells shared by both boxes, if any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.overlaps(other).then(|| Self {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        })
+    }
+
+    #[must_use]
+    pub fn translate(&self, offset: IVec3) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    /// Returns the box moved along `z` so that its bottom is at `z`.
+    #[must_use]
+    pub fn with_bottom(&self, z: i32) -> Self {
+        self.translate(IVec3::Z * (z - self.min.z))
+    }
+
+    /// Iterates over all cells, with `x` changing fastest.
+    pub fn cells(&self) -> impl Iterator<Item = IVec3> {
+        let (min, max) = (self.min, self.max);
+        (min.z..=max.z).flat_map(move |z| {
+            (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec3::new(x, y, z)))
+        })
+    }
+
+    /// Iterates over the `(x, y)` columns covered by the box.
+    pub fn footprint(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| (x, y)))
+    }
+}
+
+impl Display for Aabb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{},{},{}~{},{},{}",
+            self.min.x, self.min.y, self.min.z, self.max.x, self.max.y, self.max.z
+        )
+    }
+}
+
+/// A sparse map of occupied cells, remembering which box occupies each of them.
+#[derive(Debug, Clone, Default)]
+pub struct Occupancy {
+    cells: HashMap<IVec3, usize>,
+}
+
+impl Occupancy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the cells of the box as occupied by `id`, replacing previous occupants.
+    pub fn insert(&mut self, id: usize, aabb: &Aabb) {
+        self.cells.extend(aabb.cells().map(|cell| (cell, id)));
+    }
+
+    /// Frees the cells of the box that are occupied by `id`.
+    pub fn remove(&mut self, id: usize, aabb: &Aabb) {
+        for cell in aabb.cells() {
+            if self.cells.get(&cell) == Some(&id) {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    /// Returns the id of the box occupying the cell.
+    pub fn get(&self, cell: IVec3) -> Option<usize> {
+        self.cells.get(&cell).copied()
+    }
+
+    /// Returns whether none of the cells of the box are occupied.
+    pub fn is_free(&self, aabb: &Aabb) -> bool {
+        aabb.cells().all(|cell| !self.cells.contains_key(&cell))
+    }
+
+    /// Returns the ids of the boxes occupying any cell of `aabb`, sorted and without duplicates.
+    pub fn occupants(&self, aabb: &Aabb) -> Vec<usize> {
+        let mut ids: Vec<usize> = aabb.cells().filter_map(|cell| self.get(cell)).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Returns the number of occupied cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+/// Boxes after dropping them along `z` until they rest on the floor or on other boxes.
+///
+/// Ids are the indices of the boxes as given to [`settle`].
+#[derive(Debug, Clone)]
+pub struct Settled {
+    /// The boxes at their resting positions.
+    pub boxes: Vec<Aabb>,
+    /// For every box, the boxes resting directly on top of it.
+    pub supports: Vec<Vec<usize>>,
+    /// For every box, the boxes it rests on directly. Empty for boxes on the floor.
+    pub supported_by: Vec<Vec<usize>>,
+}
+
+impl Settled {
+    /// Returns whether the box can be removed without any other box falling down.
+    pub fn is_removable(&self, id: usize) -> bool {
+        self.supports[id]
+            .iter()
+            .all(|&above| self.supported_by[above].len() > 1)
+    }
+
+    /// Returns the ids in an order where every box comes after all boxes it rests on.
+    pub fn bottom_up(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.boxes.len()).collect();
+        order.sort_by_key(|&id| self.boxes[id].min.z);
+        order
+    }
+}
+
+/// Drops every box along `-z` until it rests on top of another box or its bottom reaches `floor`.
+///
+/// Boxes are dropped in the order of their bottoms, so a box never passes through one below it. Boxes must not
+/// overlap initially.
+pub fn settle(boxes: &[Aabb], floor: i32) -> Settled {
+    let mut order: Vec<usize> = (0..boxes.len()).collect();
+    order.sort_by_key(|&id| boxes[id].min.z);
+
+    // the top of the highest box in every (x, y) column, and which box that is.
+    let mut heights: HashMap<(i32, i32), (i32, usize)> = HashMap::new();
+    let mut settled = boxes.to_vec();
+    let mut supports = vec![vec![]; boxes.len()];
+    let mut supported_by = vec![vec![]; boxes.len()];
+
+    for id in order {
+        let aabb = boxes[id];
+        let below: Vec<(i32, usize)> = aabb
+            .footprint()
+            .filter_map(|column| heights.get(&column).copied())
+            .collect();
+        let bottom = below
+            .iter()
+            .map(|(top, _)| top + 1)
+            .max()
+            .unwrap_or(floor)
+            .max(floor);
+
+        let mut resting_on: Vec<usize> = below
+            .iter()
+            .filter(|(top, _)| top + 1 == bottom)
+            .map(|(_, below)| *below)
+            .collect();
+        resting_on.sort_unstable();
+        resting_on.dedup();
+        for &below in &resting_on {
+            supports[below].push(id);
+        }
+        supported_by[id] = resting_on;
+
+        let aabb = aabb.with_bottom(bottom);
+        for column in aabb.footprint() {
+            heights.insert(column, (aabb.max.z, id));
+        }
+        settled[id] = aabb;
+    }
+
+    Settled {
+        boxes: settled,
+        supports,
+        supported_by,
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{settle, Aabb, Occupancy};
+    use glam::IVec3;
+
+    fn brick(a: [i32; 3], b: [i32; 3]) -> Aabb {
+        Aabb::new(IVec3::from(a), IVec3::from(b))
+    }
+
+    #[test]
+    fn compares_boxes() {
+        let a = brick([2, 0, 5], [0, 2, 3]);
+        assert_eq!(a.min, IVec3::new(0, 0, 3));
+        assert_eq!(a.size(), IVec3::new(3, 3, 3));
+        assert_eq!(a.volume(), 27);
+        assert_eq!(a.cells().count(), 27);
+        assert_eq!(a.footprint().count(), 9);
+
+        let b = brick([2, 2, 7], [4, 2, 7]);
+        assert!(!a.overlaps(&b));
+        assert!(a.overlaps_xy(&b));
+        assert_eq!(
+            a.intersection(&b.translate(IVec3::new(0, 0, -2))),
+            Some(brick([2, 2, 5], [2, 2, 5]))
+        );
+        assert_eq!(b.with_bottom(1).to_string(), "2,2,1~4,2,1");
+    }
+
+    #[test]
+    fn tracks_occupancy() {
+        let mut occupancy = Occupancy::new();
+        occupancy.insert(0, &brick([0, 0, 1], [2, 0, 1]));
+        occupancy.insert(1, &brick([1, 0, 2], [1, 0, 3]));
+        assert_eq!(occupancy.len(), 5);
+        assert_eq!(occupancy.get(IVec3::new(1, 0, 3)), Some(1));
+        assert_eq!(
+            occupancy.occupants(&brick([0, 0, 0], [1, 0, 2])),
+            vec![0, 1]
+        );
+        assert!(occupancy.is_free(&brick([0, 0, 2], [0, 0, 5])));
+
+        occupancy.remove(1, &brick([1, 0, 2], [1, 0, 3]));
+        assert_eq!(occupancy.len(), 3);
+    }
+
+    #[test]
+    fn settles_boxes() {
+        let boxes = [
+            brick([0, 0, 5], [2, 0, 5]),
+            brick([0, 0, 2], [0, 2, 2]),
+            brick([2, 0, 9], [2, 2, 9]),
+            brick([1, 0, 10], [1, 0, 10]),
+        ];
+        let settled = settle(&boxes, 1);
+        assert_eq!(settled.boxes[1].min.z, 1);
+        assert_eq!(settled.boxes[0].min.z, 2);
+        assert_eq!(settled.boxes[2].min.z, 3);
+        assert_eq!(settled.boxes[3].min.z, 3);
+        assert_eq!(settled.supported_by[0], vec![1]);
+        assert_eq!(settled.supports[0], vec![2, 3]);
+        assert!(settled.supported_by[1].is_empty());
+        assert!(!settled.is_removable(0));
+        assert!(settled.is_removable(2));
+        assert_eq!(settled.bottom_up()[..2], [1, 0]);
+    }
+}